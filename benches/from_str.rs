@@ -0,0 +1,41 @@
+// LICENSE: CC0-1.0
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use text_position_rs::{CompositePosition, TextPosition, Utf16Position};
+
+fn make_text(line: &str) -> String {
+    // Long lines so that counting columns dominates.
+    let mut text = String::new();
+    for _ in 0..1000 {
+        for _ in 0..20 {
+            text += line;
+        }
+        text += "\n";
+    }
+    text
+}
+
+fn bench_from_str(c: &mut Criterion) {
+    let ascii = make_text("let x = 42; ");
+    let mixed = make_text("let x = \"いろは\"; ");
+
+    // The final line is long, since only that line counts columns.
+    let ascii = format!("{}{}", ascii, ascii.replace('\n', ""));
+    let mixed = format!("{}{}", mixed, mixed.replace('\n', ""));
+
+    c.bench_function("composite_from_str_ascii", |b| {
+        b.iter(|| CompositePosition::from_str(black_box(&ascii)))
+    });
+    c.bench_function("composite_from_str_mixed", |b| {
+        b.iter(|| CompositePosition::from_str(black_box(&mixed)))
+    });
+    c.bench_function("utf16_from_str_ascii", |b| {
+        b.iter(|| Utf16Position::from_str(black_box(&ascii)))
+    });
+    c.bench_function("utf16_from_str_mixed", |b| {
+        b.iter(|| Utf16Position::from_str(black_box(&mixed)))
+    });
+}
+
+criterion_group!(benches, bench_from_str);
+criterion_main!(benches);
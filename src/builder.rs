@@ -0,0 +1,221 @@
+// LICENSE: CC0-1.0
+
+use crate::{CompositePosition, TextPosition};
+use std::fmt::{self, Write};
+
+/// Accumulator of text position, useful to track the current position while scanning text.
+///
+/// ```
+/// use text_position_rs::{CompositePosition, PositionBuilder};
+///
+/// let mut builder = PositionBuilder::<CompositePosition>::new();
+/// builder.push_char('a');
+/// builder.push_char('b');
+/// builder.push_char('\n');
+/// builder.push_str("cd");
+///
+/// assert_eq!(builder.position(), CompositePosition::new(5, 1, 2, 2));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PositionBuilder<P> {
+    current: P,
+}
+
+impl<P: TextPosition + From<char>> PositionBuilder<P> {
+    /// Create a builder pointing to the origin.
+    pub fn new() -> Self {
+        Self { current: P::ZERO }
+    }
+
+    /// Advance the position by a character.
+    pub fn push_char(&mut self, c: char) {
+        self.current = self.current.clone() + P::from(c);
+    }
+
+    /// Advance the position by a string.
+    pub fn push_str(&mut self, s: &str) {
+        self.current = self.current.clone() + P::from_str(s);
+    }
+
+    /// Current position.
+    pub fn position(&self) -> P {
+        self.current.clone()
+    }
+}
+
+/// Stateful consumer of characters that treats `\r\n` as a single line break.
+///
+/// `From<char>` is stateless, so feeding `\r\n` char by char counts the `\r` in the column of the previous line
+/// until `\n` arrives. This tracker holds a `\r` back until the next character is known,
+/// so that the position never points between `\r` and `\n`.
+///
+/// ```
+/// use text_position_rs::{LineBreakTracker, Utf8Position};
+///
+/// let mut tracker = LineBreakTracker::<Utf8Position>::new();
+/// tracker.push_char('a');
+/// tracker.push_char('\r');
+/// assert_eq!(tracker.position(), Utf8Position::new(0, 1));
+///
+/// assert_eq!(tracker.push_char('\n'), Utf8Position::new(1, 0));
+/// assert_eq!(tracker.position(), Utf8Position::new(1, 0));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct LineBreakTracker<P> {
+    current: P,
+    pending_cr: bool,
+}
+
+impl<P: TextPosition + From<char>> LineBreakTracker<P> {
+    /// Create a tracker pointing to the origin.
+    pub fn new() -> Self {
+        Self {
+            current: P::ZERO,
+            pending_cr: false,
+        }
+    }
+
+    /// Consume a character and return the delta of the position.
+    ///
+    /// The delta of `\r` is `ZERO` until the next character arrives.
+    /// The delta of `\n` after `\r` is that of `\r\n` (so `CompositePosition::index` counts both bytes).
+    pub fn push_char(&mut self, c: char) -> P {
+        let delta = match (self.pending_cr, c) {
+            (true, '\n') => P::from_str("\r\n"),
+            (true, '\r') => P::from('\r'),
+            (true, _) => P::from('\r') + P::from(c),
+            (false, '\r') => P::ZERO,
+            (false, _) => P::from(c),
+        };
+        self.pending_cr = c == '\r';
+        self.current = self.current.clone() + delta.clone();
+        delta
+    }
+
+    /// Count a pending `\r`, if any, and return the delta of the position.
+    ///
+    /// Call this at the end of input.
+    pub fn finish(&mut self) -> P {
+        if !self.pending_cr {
+            return P::ZERO;
+        }
+
+        self.pending_cr = false;
+        let delta = P::from('\r');
+        self.current = self.current.clone() + delta.clone();
+        delta
+    }
+
+    /// Current position. A pending `\r` isn't counted.
+    pub fn position(&self) -> P {
+        self.current.clone()
+    }
+}
+
+/// Wrapper of `fmt::Write` that tracks the position at the end of written text.
+///
+/// ```
+/// use std::fmt::Write;
+/// use text_position_rs::{CompositePosition, PositionWriter};
+///
+/// let mut writer = PositionWriter::new(String::new());
+/// write!(writer, "fn {}() {{\n}}", "main").unwrap();
+/// assert_eq!(writer.position(), CompositePosition::new(13, 1, 1, 1));
+/// assert_eq!(writer.into_inner(), "fn main() {\n}");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PositionWriter<W> {
+    inner: W,
+    current: CompositePosition,
+}
+
+impl<W: Write> PositionWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            current: CompositePosition::ZERO,
+        }
+    }
+
+    /// Position at the end of text written so far.
+    pub fn position(&self) -> CompositePosition {
+        self.current
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for PositionWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_str(s)?;
+        self.current += CompositePosition::from_str(s);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        CompositePosition, LineBreakTracker, PositionBuilder, PositionWriter, TextPosition,
+        Utf16Position, Utf8Position,
+    };
+    use std::fmt::Write;
+
+    #[test]
+    fn test_push_char_agrees_with_from_str() {
+        let s = "いろは\n🐧🐧\r\nxyz";
+        let mut builder = PositionBuilder::<Utf16Position>::new();
+        for c in s.chars() {
+            builder.push_char(c);
+        }
+        assert_eq!(builder.position(), Utf16Position::from_str(s));
+    }
+
+    #[test]
+    fn test_push_str_agrees_with_from_str() {
+        let mut builder = PositionBuilder::<Utf8Position>::new();
+        builder.push_str("12\n345");
+        builder.push_str("\n");
+        builder.push_str("6789");
+        assert_eq!(builder.position(), Utf8Position::from_str("12\n345\n6789"));
+    }
+
+    #[test]
+    fn test_line_break_tracker() {
+        let s = "ab\r\ncd\r\r\nx\ry\r";
+        let mut tracker = LineBreakTracker::<CompositePosition>::new();
+        let mut total = CompositePosition::ZERO;
+        for (i, c) in s.char_indices() {
+            total += tracker.push_char(c);
+            assert_eq!(tracker.position(), total);
+
+            // The position never points after a `\r`.
+            let prefix = &s[..i + 1];
+            let expected = prefix.strip_suffix('\r').unwrap_or(prefix);
+            assert_eq!(tracker.position(), CompositePosition::from_str(expected));
+        }
+
+        total += tracker.finish();
+        assert_eq!(total, CompositePosition::from_str(s));
+        assert_eq!(tracker.position(), CompositePosition::from_str(s));
+        assert_eq!(tracker.finish(), CompositePosition::ZERO);
+    }
+
+    #[test]
+    fn test_position_writer() {
+        let mut writer = PositionWriter::new(String::new());
+        writer.write_str("ab\ncd").unwrap();
+        let pos = writer.position();
+        assert_eq!((pos.row, pos.column8), (1, 2));
+
+        write!(writer, "{}\n🐧", 42).unwrap();
+        assert_eq!(writer.position(), CompositePosition::new(12, 2, 4, 2));
+        assert_eq!(writer.get_ref(), "ab\ncd42\n🐧");
+    }
+}
@@ -0,0 +1,103 @@
+// LICENSE: CC0-1.0
+
+use crate::{
+    ByteCharPosition, CompositePosition, TextPosition, Utf16Position, Utf8Index, Utf8Position,
+};
+use std::ops::Add;
+
+// DESIGN: `TextRange::len` stays `P` for compatibility.
+//         Use `TextRange::delta` to get it as `Delta<P>`.
+
+/// Relative distance between two positions, as opposed to an absolute position.
+///
+/// Adding a delta to a position makes a position; adding two positions is still possible but not intended.
+///
+/// ```
+/// use text_position_rs::{Delta, TextPosition, Utf8Position};
+///
+/// let start = Utf8Position::new(2, 4);
+/// let delta = Delta::between(start, Utf8Position::new(3, 1));
+/// assert_eq!(start + delta, Utf8Position::new(3, 1));
+/// ```
+#[derive(Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Delta<P>(pub P);
+
+impl<P: TextPosition> Delta<P> {
+    pub const ZERO: Self = Delta(P::ZERO);
+
+    /// Distance from `start` to `end`. `ZERO` if `end <= start`.
+    pub fn between(start: P, end: P) -> Self {
+        Delta(end.saturating_sub(start))
+    }
+
+    /// Delta measured by a string.
+    pub fn of_str(s: &str) -> Self {
+        Delta(P::from_str(s))
+    }
+
+    /// Move a position by the delta.
+    pub fn apply(self, pos: P) -> P {
+        pos + self.0
+    }
+
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+impl<P: TextPosition> Add for Delta<P> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Delta(self.0 + rhs.0)
+    }
+}
+
+impl Add<Delta<Utf8Index>> for Utf8Index {
+    type Output = Self;
+
+    fn add(self, rhs: Delta<Self>) -> Self {
+        rhs.apply(self)
+    }
+}
+
+impl Add<Delta<Utf8Position>> for Utf8Position {
+    type Output = Self;
+
+    fn add(self, rhs: Delta<Self>) -> Self {
+        rhs.apply(self)
+    }
+}
+
+impl Add<Delta<Utf16Position>> for Utf16Position {
+    type Output = Self;
+
+    fn add(self, rhs: Delta<Self>) -> Self {
+        rhs.apply(self)
+    }
+}
+
+impl Add<Delta<CompositePosition>> for CompositePosition {
+    type Output = Self;
+
+    fn add(self, rhs: Delta<Self>) -> Self {
+        rhs.apply(self)
+    }
+}
+
+impl Add<Delta<ByteCharPosition>> for ByteCharPosition {
+    type Output = Self;
+
+    fn add(self, rhs: Delta<Self>) -> Self {
+        rhs.apply(self)
+    }
+}
+
+#[cfg(feature = "index64")]
+impl Add<Delta<crate::Utf8Index64>> for crate::Utf8Index64 {
+    type Output = Self;
+
+    fn add(self, rhs: Delta<Self>) -> Self {
+        rhs.apply(self)
+    }
+}
@@ -0,0 +1,129 @@
+// LICENSE: CC0-1.0
+
+use crate::{PositionError, TextRange, Utf8Index};
+
+/// Set of edits to a text, to map positions before the edits to positions after them.
+///
+/// Each edit replaces a range of the old text with a text of `new_len` bytes.
+///
+/// ```
+/// use text_position_rs::{EditSet, TextRange, Utf8Index};
+///
+/// // "let x = 1;" -> "let value = 42;"
+/// let mut edits = EditSet::new();
+/// edits.insert(TextRange::from(Utf8Index::new(4)..Utf8Index::new(5)), Utf8Index::new(5)).unwrap();
+/// edits.insert(TextRange::from(Utf8Index::new(8)..Utf8Index::new(9)), Utf8Index::new(2)).unwrap();
+///
+/// // Position of ";".
+/// assert_eq!(edits.remap(Utf8Index::new(9)), Utf8Index::new(14));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EditSet {
+    /// Pairs of replaced range and length of new text, sorted by position.
+    edits: Vec<(TextRange<Utf8Index>, Utf8Index)>,
+}
+
+impl EditSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an edit.
+    ///
+    /// Fail if the range overlaps with a range of another edit or starts at the same position.
+    pub fn insert(
+        &mut self,
+        range: TextRange<Utf8Index>,
+        new_len: Utf8Index,
+    ) -> Result<(), PositionError> {
+        let i = self
+            .edits
+            .partition_point(|(other, _)| other.start() < range.start());
+
+        let overlaps_prev = i > 0 && self.edits[i - 1].0.end() > range.start();
+        let overlaps_next = self.edits.get(i).is_some_and(|(other, _)| {
+            other.start() < range.end() || other.start() == range.start()
+        });
+        if overlaps_prev || overlaps_next {
+            return Err(PositionError::OverlappingEdits);
+        }
+
+        self.edits.insert(i, (range, new_len));
+        Ok(())
+    }
+
+    /// Map a position in the old text to the position in the new text.
+    ///
+    /// A position at the start of an edit stays at the start.
+    /// A position inside a replaced range is clamped to the start of the edit.
+    pub fn remap(&self, pos: Utf8Index) -> Utf8Index {
+        let shift = |index: u32, shift: i64| Utf8Index::new((index as i64 + shift) as u32);
+
+        let mut delta = 0_i64;
+        for (range, new_len) in &self.edits {
+            if pos <= range.start() {
+                break;
+            }
+            if pos < range.end() {
+                return shift(range.start().index, delta);
+            }
+            delta += new_len.index as i64 - range.len.index as i64;
+        }
+        shift(pos.index, delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EditSet, PositionError, TextRange, Utf8Index};
+
+    fn range(start: u32, end: u32) -> TextRange<Utf8Index> {
+        TextRange::from(Utf8Index::new(start)..Utf8Index::new(end))
+    }
+
+    fn remap(edits: &EditSet, pos: u32) -> u32 {
+        edits.remap(Utf8Index::new(pos)).index
+    }
+
+    #[test]
+    fn test_remap_multiple_edits() {
+        // "0123456789" -> "0ab3456x9"
+        let mut edits = EditSet::new();
+        edits.insert(range(7, 9), Utf8Index::new(1)).unwrap();
+        edits.insert(range(1, 3), Utf8Index::new(2)).unwrap();
+        edits.insert(range(3, 3), Utf8Index::new(0)).unwrap();
+
+        assert_eq!(remap(&edits, 0), 0);
+        assert_eq!(remap(&edits, 1), 1);
+        assert_eq!(remap(&edits, 4), 4);
+        assert_eq!(remap(&edits, 9), 8);
+        assert_eq!(remap(&edits, 10), 9);
+    }
+
+    #[test]
+    fn test_remap_inside_replaced_range() {
+        let mut edits = EditSet::new();
+        edits.insert(range(2, 6), Utf8Index::new(1)).unwrap();
+        assert_eq!(remap(&edits, 4), 2);
+        assert_eq!(remap(&edits, 6), 3);
+    }
+
+    #[test]
+    fn test_insert_overlapping() {
+        let mut edits = EditSet::new();
+        edits.insert(range(2, 6), Utf8Index::new(1)).unwrap();
+
+        for &(start, end) in &[(1, 3), (5, 8), (3, 4), (0, 9), (2, 2), (4, 4)] {
+            assert_eq!(
+                edits.insert(range(start, end), Utf8Index::new(0)),
+                Err(PositionError::OverlappingEdits),
+                "{:?}",
+                (start, end)
+            );
+        }
+
+        // Adjacent edits are fine.
+        edits.insert(range(6, 7), Utf8Index::new(0)).unwrap();
+        edits.insert(range(0, 2), Utf8Index::new(0)).unwrap();
+    }
+}
@@ -0,0 +1,72 @@
+// LICENSE: CC0-1.0
+
+use crate::CompositePosition;
+
+// DESIGN: No variant for UTF-32 columns (number of characters)
+//         since `CompositePosition` doesn't track them.
+//         Supporting them requires a `column32` field, which is a breaking change.
+
+/// Unit of columns, as negotiated by `positionEncoding` of LSP 3.17.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PositionEncoding {
+    /// Columns in UTF-8 code units (bytes).
+    Utf8,
+
+    /// Columns in UTF-16 code units. Default of LSP.
+    #[default]
+    Utf16,
+}
+
+impl PositionEncoding {
+    /// Name used in LSP, e.g. `"utf-16"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PositionEncoding::Utf8 => "utf-8",
+            PositionEncoding::Utf16 => "utf-16",
+        }
+    }
+
+    /// Inverse of `as_str`. Return `None` for `"utf-32"`, which isn't supported.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "utf-8" => Some(PositionEncoding::Utf8),
+            "utf-16" => Some(PositionEncoding::Utf16),
+            _ => None,
+        }
+    }
+}
+
+impl CompositePosition {
+    /// Get `(row, column)` with column in the unit of the encoding.
+    ///
+    /// ```
+    /// use text_position_rs::{CompositePosition, PositionEncoding, TextPosition};
+    ///
+    /// let pos = CompositePosition::from_str("ab\n🐧");
+    /// assert_eq!(pos.encode(PositionEncoding::Utf8), (1, 4));
+    /// assert_eq!(pos.encode(PositionEncoding::Utf16), (1, 2));
+    /// ```
+    pub fn encode(self, encoding: PositionEncoding) -> (u32, u32) {
+        match encoding {
+            PositionEncoding::Utf8 => (self.row, self.column8),
+            PositionEncoding::Utf16 => (self.row, self.column16),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PositionEncoding;
+
+    #[test]
+    fn test_name_round_trip() {
+        for &encoding in &[PositionEncoding::Utf8, PositionEncoding::Utf16] {
+            assert_eq!(
+                PositionEncoding::from_name(encoding.as_str()),
+                Some(encoding)
+            );
+        }
+        assert_eq!(PositionEncoding::from_name("utf16"), None);
+        assert_eq!(PositionEncoding::from_name("utf-32"), None);
+    }
+}
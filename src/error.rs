@@ -0,0 +1,69 @@
+// LICENSE: CC0-1.0
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    num::ParseIntError,
+};
+
+/// Error of parsing or converting text positions.
+///
+/// More variants can be added in minor versions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PositionError {
+    /// Separator between numbers (e.g. `:` of `row:column`) is missing.
+    MissingSeparator,
+
+    /// Some part isn't a valid number.
+    InvalidNumber(ParseIntError),
+
+    /// Position points to the middle of a character.
+    NotACharBoundary,
+
+    /// Position is beyond the end of text.
+    OutOfBounds,
+
+    /// Measures of a position can't be of the same position,
+    /// e.g. `(index, row, column8, column16)` of `CompositePosition`.
+    Inconsistent((u32, u32, u32, u32)),
+
+    /// Edits to a text overlap with each other.
+    OverlappingEdits,
+
+    /// Range isn't covered by its parent range.
+    NotNested,
+}
+
+impl Display for PositionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PositionError::MissingSeparator => write!(f, "missing separator"),
+            PositionError::InvalidNumber(err) => write!(f, "invalid number: {}", err),
+            PositionError::NotACharBoundary => write!(f, "not a char boundary"),
+            PositionError::OutOfBounds => write!(f, "out of bounds"),
+            PositionError::Inconsistent((index, row, column8, column16)) => write!(
+                f,
+                "inconsistent position (index={}, row={}, column8={}, column16={})",
+                index, row, column8, column16
+            ),
+            PositionError::OverlappingEdits => write!(f, "overlapping edits"),
+            PositionError::NotNested => write!(f, "range not nested in parent"),
+        }
+    }
+}
+
+impl Error for PositionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PositionError::InvalidNumber(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseIntError> for PositionError {
+    fn from(err: ParseIntError) -> Self {
+        PositionError::InvalidNumber(err)
+    }
+}
@@ -1,10 +1,37 @@
 // LICENSE: CC0-1.0
 
+mod builder;
+mod delta;
+mod edit_set;
+mod encoding;
+mod error;
+mod line_index;
+mod macros;
 mod position;
 mod range;
+mod span_stack;
 
+#[cfg(feature = "serde")]
+pub mod serde_tuple;
+
+pub use builder::{LineBreakTracker, PositionBuilder, PositionWriter};
+pub use delta::Delta;
+pub use edit_set::EditSet;
+pub use encoding::PositionEncoding;
+pub use error::PositionError;
+pub use line_index::{newline_indices, LineIndex};
 pub use position::{
-    composite_position::CompositePosition, utf16_position::Utf16Position, utf8_index::Utf8Index,
-    utf8_position::Utf8Position, TextPosition,
+    byte_char_position::ByteCharPosition,
+    column16_at, column_at,
+    composite_position::{CompositePosition, CompositePositionFullKey},
+    final_column_of, row_of,
+    utf16_position::Utf16Position,
+    utf8_index::Utf8Index,
+    utf8_position::Utf8Position,
+    TextPosition,
 };
 pub use range::TextRange;
+pub use span_stack::SpanStack;
+
+#[cfg(feature = "index64")]
+pub use position::utf8_index64::Utf8Index64;
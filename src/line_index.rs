@@ -0,0 +1,131 @@
+// LICENSE: CC0-1.0
+
+use crate::{TextPosition, Utf8Index, Utf8Position};
+
+/// Table of the start index of each line in a text,
+/// to convert `Utf8Index` to `Utf8Position` without scanning the text.
+///
+/// ```
+/// use text_position_rs::{LineIndex, Utf8Index, Utf8Position};
+///
+/// let line_index = LineIndex::new("ab\ncd\nef");
+/// assert_eq!(line_index.position(Utf8Index::new(4)), Utf8Position::new(1, 1));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LineIndex {
+    /// Index of the start of each line. The first element is always 0.
+    line_starts: Vec<u32>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(newline_indices(text).map(|index| index.index));
+        Self { line_starts }
+    }
+
+    /// Number of lines. A text without newline has one line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Calculate the position at an index.
+    pub fn position(&self, index: Utf8Index) -> Utf8Position {
+        let row = self
+            .line_starts
+            .partition_point(|&start| start <= index.index)
+            - 1;
+        Utf8Position::new(row as u32, index.index - self.line_starts[row])
+    }
+
+    /// Calculate the positions at indices, in the same order as the input.
+    ///
+    /// This sorts the indices and walks lines once,
+    /// which is faster than calling `position` repeatedly for many indices.
+    pub fn positions_batch(&self, indices: &[Utf8Index]) -> Vec<Utf8Position> {
+        let mut order = (0..indices.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| indices[i]);
+
+        let mut positions = vec![Utf8Position::ZERO; indices.len()];
+        let mut row = 0;
+        for i in order {
+            let index = indices[i].index;
+            while row + 1 < self.line_starts.len() && self.line_starts[row + 1] <= index {
+                row += 1;
+            }
+            positions[i] = Utf8Position::new(row as u32, index - self.line_starts[row]);
+        }
+        positions
+    }
+}
+
+/// Iterate over indices just after each `\n`, i.e. starts of lines except for the first line.
+///
+/// This is what `LineIndex` is built from.
+///
+/// ```
+/// use text_position_rs::{newline_indices, Utf8Index};
+///
+/// let indices = newline_indices("ab\ncd\n").collect::<Vec<_>>();
+/// assert_eq!(indices, vec![Utf8Index::new(3), Utf8Index::new(6)]);
+/// ```
+pub fn newline_indices(text: &str) -> impl Iterator<Item = Utf8Index> + '_ {
+    #[cfg(feature = "memchr")]
+    let offsets = memchr::memchr_iter(b'\n', text.as_bytes());
+
+    #[cfg(not(feature = "memchr"))]
+    let offsets = text.match_indices('\n').map(|(offset, _)| offset);
+
+    offsets.map(|offset| Utf8Index::new((offset + 1) as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{newline_indices, LineIndex, TextPosition, Utf8Index, Utf8Position};
+
+    const TEXT: &str = "fn main() {\n    println!(\"いろは\");\n}\n";
+
+    #[test]
+    fn test_position_agrees_with_from_str() {
+        let line_index = LineIndex::new(TEXT);
+        for (i, _) in TEXT.char_indices() {
+            assert_eq!(
+                line_index.position(Utf8Index::new(i as u32)),
+                Utf8Position::from_str(&TEXT[..i])
+            );
+        }
+    }
+
+    #[test]
+    fn test_positions_batch_keeps_order() {
+        let line_index = LineIndex::new(TEXT);
+        let indices = [30, 0, 12, 45, 16, 11]
+            .iter()
+            .map(|&i| Utf8Index::new(i))
+            .collect::<Vec<_>>();
+
+        let expected = indices
+            .iter()
+            .map(|&index| line_index.position(index))
+            .collect::<Vec<_>>();
+        assert_eq!(line_index.positions_batch(&indices), expected);
+    }
+
+    #[test]
+    fn test_line_count() {
+        assert_eq!(LineIndex::new("").line_count(), 1);
+        assert_eq!(LineIndex::new(TEXT).line_count(), 4);
+    }
+
+    #[test]
+    fn test_newline_indices_crlf() {
+        let text = "a\r\nbc\r\n\r\nd";
+        let indices = newline_indices(text)
+            .map(|index| index.index)
+            .collect::<Vec<_>>();
+        assert_eq!(indices, vec![3, 7, 9]);
+        for &i in &indices {
+            assert_eq!(text.as_bytes()[i as usize - 1], b'\n');
+        }
+    }
+}
@@ -0,0 +1,44 @@
+// LICENSE: CC0-1.0
+
+/// Create a `Utf8Position` from 1-based `row:column`, in the same notation as `Display`.
+///
+/// Zero in the literal is an error at compile time.
+///
+/// ```
+/// use text_position_rs::{pos, Utf8Position};
+///
+/// const POS: Utf8Position = pos!(4:6);
+/// assert_eq!(POS, Utf8Position::new(3, 5));
+/// assert_eq!(format!("{}", POS), "4:6");
+/// ```
+#[macro_export]
+macro_rules! pos {
+    ($row:literal : $column:literal) => {
+        $crate::Utf8Position::new($row - 1, $column - 1)
+    };
+}
+
+/// Create a `TextRange<Utf8Position>` from 1-based `row:column - row:column`.
+///
+/// Unlike `Display`, this takes `:` between row and column, same as `pos!`.
+///
+/// ```
+/// use text_position_rs::{range, TextRange, Utf8Position};
+///
+/// const RANGE: TextRange<Utf8Position> = range!(1:8 - 1:13);
+/// assert_eq!(format!("{}", RANGE), "1.8-1.13");
+/// assert_eq!(format!("{}", range!(2:3 - 4:1)), "2.3-4.1");
+/// ```
+#[macro_export]
+macro_rules! range {
+    ($start_row:literal : $start_column:literal - $end_row:literal : $end_column:literal) => {
+        $crate::TextRange::at(
+            $crate::pos!($start_row:$start_column),
+            if $start_row == $end_row {
+                $crate::Utf8Position::new(0, $end_column - $start_column)
+            } else {
+                $crate::Utf8Position::new($end_row - $start_row, $end_column - 1)
+            },
+        )
+    };
+}
@@ -1,22 +1,860 @@
 // LICENSE: CC0-1.0
 
-use std::ops::Add;
+use crate::Utf8Index;
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::Add,
+};
 
+pub(crate) mod byte_char_position;
 pub(crate) mod composite_position;
 pub(crate) mod utf16_position;
 pub(crate) mod utf8_index;
+#[cfg(feature = "index64")]
+pub(crate) mod utf8_index64;
 pub(crate) mod utf8_position;
 
+// DESIGN: No associated type for the scalar (1-D) form of a position.
+//         Row-column pairs have no such form, and a required associated type would break implementors.
+//         Length of a range is a `P` measured from the start (see `Delta<P>`),
+//         and `byte_len` gives the scalar form where it's known.
+//
+// DESIGN: `MAX` is required even though it breaks implementors (since 0.5.0),
+//         because a constant can't be computed from other items, unlike the provided methods.
+
 /// Some representation of text position.
+///
+/// On overflow of fields, arithmetic behaves in one of three ways:
+///
+/// - `+` panics in debug builds and wraps in release builds by default, same as `u32`.
+/// - `+` panics in both builds if `strict-overflow` feature is enabled.
+/// - `checked_add` and `saturating_add` never panic nor wrap, regardless of features.
+///
+/// For intentional modular arithmetic, use `Utf8Index::wrapping_add`,
+/// or `u32::wrapping_add` on fields of other positions.
 pub trait TextPosition: Clone + Ord + Add<Output = Self> {
     /// Origin.
     const ZERO: Self;
 
+    /// Position with all fields maximum, which is greater than or equal to any position.
+    ///
+    /// This is a sentinel, e.g. for initial value of `min`. Arithmetic on it will overflow.
+    ///
+    /// Required since 0.5.0. Implementors should set every field to its maximum.
+    const MAX: Self;
+
     /// Calculate a text position pointing to the end of string.
+    ///
+    /// This agrees with addition: `from_str(a) + from_str(b) == from_str(a + b)`.
     fn from_str(s: &str) -> Self;
 
+    /// Calculate a text position pointing to the end of string, along with the length of string in bytes.
+    ///
+    /// ```
+    /// use text_position_rs::{TextPosition, Utf16Position, Utf8Index};
+    ///
+    /// let (pos, len) = Utf16Position::measure("ab\n🐧");
+    /// assert_eq!(pos, Utf16Position::new(1, 2));
+    /// assert_eq!(len, Utf8Index::new(7));
+    /// ```
+    fn measure(s: &str) -> (Self, Utf8Index) {
+        (Self::from_str(s), Utf8Index::new(s.len() as u32))
+    }
+
+    /// Calculate a text position pointing to the end of string, ignoring a final newline if any.
+    ///
+    /// Final `\r\n` is also ignored as a whole.
+    /// This is useful to point to the end of the final line rather than an empty line after it.
+    ///
+    /// ```
+    /// use text_position_rs::{TextPosition, Utf8Position};
+    ///
+    /// assert_eq!(Utf8Position::from_str_trim_final_newline("abc\n"), Utf8Position::new(0, 3));
+    /// assert_eq!(Utf8Position::from_str_trim_final_newline("abc\r\n"), Utf8Position::new(0, 3));
+    /// assert_eq!(Utf8Position::from_str_trim_final_newline("abc\n\n"), Utf8Position::new(1, 0));
+    /// ```
+    fn from_str_trim_final_newline(s: &str) -> Self {
+        let s = s
+            .strip_suffix('\n')
+            .map_or(s, |s| s.strip_suffix('\r').unwrap_or(s));
+        Self::from_str(s)
+    }
+
+    /// Calculate a text position pointing to the end of concatenation of chunks, without concatenating them.
+    ///
+    /// ```
+    /// use text_position_rs::{CompositePosition, TextPosition};
+    ///
+    /// let chunks = vec!["ab", "c\nd", "e"];
+    /// assert_eq!(
+    ///     CompositePosition::from_chunks(chunks),
+    ///     CompositePosition::from_str("abc\nde")
+    /// );
+    /// ```
+    fn from_chunks<'a>(chunks: impl IntoIterator<Item = &'a str>) -> Self {
+        chunks
+            .into_iter()
+            .fold(Self::ZERO, |pos, chunk| pos + Self::from_str(chunk))
+    }
+
+    /// Calculate a text position pointing to the end of UTF-8 bytes.
+    ///
+    /// The built-in positions measure bytes directly without validation.
+    /// Result is unspecified (but no undefined behavior) if the bytes are invalid as UTF-8.
+    /// The default implementation falls back to `from_bytes_lossy`.
+    ///
+    /// ```
+    /// use text_position_rs::{TextPosition, Utf16Position};
+    ///
+    /// let bytes = "ab\n🐧".as_bytes();
+    /// assert_eq!(Utf16Position::from_bytes(bytes), Utf16Position::new(1, 2));
+    /// ```
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self::from_bytes_lossy(bytes)
+    }
+
+    /// Calculate a text position pointing to the end of bytes that can contain invalid UTF-8,
+    /// regarding each invalid byte as a single-byte character.
+    ///
+    /// Unlike measuring the result of `String::from_utf8_lossy`,
+    /// which replaces invalid bytes with `U+FFFD` (3 bytes in UTF-8),
+    /// indices agree with the original bytes.
+    ///
+    /// ```
+    /// use text_position_rs::{TextPosition, Utf16Position, Utf8Index};
+    ///
+    /// let bytes = b"ab\xFFc";
+    /// assert_eq!(Utf8Index::from_bytes_lossy(bytes), Utf8Index::new(4));
+    /// assert_eq!(Utf16Position::from_bytes_lossy(bytes), Utf16Position::new(0, 4));
+    /// ```
+    fn from_bytes_lossy(bytes: &[u8]) -> Self {
+        // Any ASCII character other than newline measures the same as a single byte.
+        let invalid_byte = Self::from_str("?");
+
+        let mut pos = Self::ZERO;
+        let mut rest = bytes;
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(s) => return pos + Self::from_str(s),
+                Err(err) => {
+                    let (valid, after) = rest.split_at(err.valid_up_to());
+                    pos = pos + Self::from_str(std::str::from_utf8(valid).unwrap());
+
+                    let invalid_len = err.error_len().unwrap_or(after.len());
+                    for _ in 0..invalid_len {
+                        pos = pos + invalid_byte.clone();
+                    }
+                    rest = &after[invalid_len..];
+                }
+            }
+        }
+    }
+
+    /// Calculate a text position pointing to the end of the first `byte_len` bytes of the text.
+    ///
+    /// `byte_len` is clamped to the length of text
+    /// and moved back to the start of a character if it's in the middle of that.
+    ///
+    /// ```
+    /// use text_position_rs::{TextPosition, Utf16Position};
+    ///
+    /// let text = "ab\n🐧";
+    /// assert_eq!(Utf16Position::from_prefix_len(7, text), Utf16Position::new(1, 2));
+    /// assert_eq!(Utf16Position::from_prefix_len(5, text), Utf16Position::new(1, 0));
+    /// ```
+    fn from_prefix_len(byte_len: usize, text: &str) -> Self {
+        let end = Utf8Index::new(byte_len.min(text.len()) as u32).snap_to_char_boundary(text);
+        Self::from_str(&text[..usize::from(end)])
+    }
+
+    /// Bound the position by the end of text.
+    ///
+    /// Note a row-column pair isn't clamped to the end of its line, only to the end of text.
+    fn clamp_to_text(self, text: &str) -> Self {
+        self.min(Self::from_str(text))
+    }
+
     /// Calculate the distance from `rhs` to `self`.
     ///
     /// Return `ZERO` if `self <= rhs`.
+    ///
+    /// This is the inverse of addition: `rhs + self.saturating_sub(rhs) == self` holds if `self >= rhs`.
     fn saturating_sub(self, rhs: Self) -> Self;
+
+    /// Addition that returns `None` on overflow instead of panicking.
+    ///
+    /// The default implementation checks `rhs` against the room left to `MAX`,
+    /// which is exact only for positions with one field. The built-in positions override it.
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        if rhs <= Self::MAX.saturating_sub(self.clone()) {
+            Some(self + rhs)
+        } else {
+            None
+        }
+    }
+
+    /// Addition that clamps each field at the maximum instead of panicking on overflow.
+    ///
+    /// The default implementation returns `MAX` if `checked_add` fails.
+    fn saturating_add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).unwrap_or(Self::MAX)
+    }
+
+    /// Combine two positions derived from a common `base`, as in a three-way merge.
+    ///
+    /// The result is `base + (a - base) + (b - base)`,
+    /// that is, the change from `base` to `a` is followed by the change from `base` to `b`.
+    /// Each change is clamped to `ZERO` if the position is before `base`.
+    ///
+    /// This is associative for a fixed `base`
+    /// (`join(base, join(base, a, b), c) == join(base, a, join(base, b, c))`),
+    /// and `base` is the identity. It's not commutative for row-column pairs
+    /// because their addition isn't.
+    ///
+    /// ```
+    /// use text_position_rs::{TextPosition, Utf8Position};
+    ///
+    /// let base = Utf8Position::from_str("fn f");
+    /// let a = Utf8Position::from_str("fn f()");
+    /// let b = Utf8Position::from_str("fn f\n");
+    /// assert_eq!(Utf8Position::join(base, a, b), Utf8Position::from_str("fn f()\n"));
+    /// assert_eq!(Utf8Position::join(base, b, a), Utf8Position::from_str("fn f\n()"));
+    /// ```
+    fn join(base: Self, a: Self, b: Self) -> Self {
+        let da = a.saturating_sub(base.clone());
+        let db = b.saturating_sub(base.clone());
+        base + da + db
+    }
+
+    /// Number of UTF-8 bytes the position measures from the origin.
+    ///
+    /// Return `None` if the position doesn't know it, e.g. row-column pairs.
+    fn byte_len(&self) -> Option<u32> {
+        None
+    }
+}
+
+/// Count the rows of string, i.e. the number of newlines.
+///
+/// Same as `Utf8Position::from_str(s).row` without measuring the column.
+///
+/// ```
+/// use text_position_rs::row_of;
+///
+/// assert_eq!(row_of("ab\ncd\n🐧"), 2);
+/// ```
+pub fn row_of(s: &str) -> u32 {
+    s.bytes().filter(|&b| b == b'\n').count() as u32
+}
+
+/// Calculate the column number in UTF-8 at the end of string, i.e. the length of the final line in bytes.
+///
+/// Same as `Utf8Position::from_str(s).column` without counting rows.
+///
+/// ```
+/// use text_position_rs::final_column_of;
+///
+/// assert_eq!(final_column_of("ab\ncd\n🐧"), 4);
+/// ```
+pub fn final_column_of(s: &str) -> u32 {
+    s.rfind('\n').map_or(s.len(), |i| s.len() - i - 1) as u32
+}
+
+/// Calculate the column number in UTF-8 at a byte offset in a line.
+///
+/// Return `None` if the offset is out of the line or in the middle of a character.
+///
+/// ```
+/// use text_position_rs::column_at;
+///
+/// assert_eq!(column_at("a🐧b", 5), Some(5));
+/// assert_eq!(column_at("a🐧b", 2), None);
+/// ```
+pub fn column_at(line: &str, byte_offset: usize) -> Option<u32> {
+    if line.is_char_boundary(byte_offset) {
+        Some(byte_offset as u32)
+    } else {
+        None
+    }
+}
+
+/// Calculate the column number in UTF-16 at a byte offset in a line.
+///
+/// Return `None` if the offset is out of the line or in the middle of a character.
+///
+/// ```
+/// use text_position_rs::column16_at;
+///
+/// assert_eq!(column16_at("a🐧b", 5), Some(3));
+/// assert_eq!(column16_at("a🐧b", 7), None);
+/// ```
+pub fn column16_at(line: &str, byte_offset: usize) -> Option<u32> {
+    let prefix = line.get(..byte_offset)?;
+    Some(prefix.encode_utf16().count() as u32)
+}
+
+/// Format a row-column pair as `row:column` (1-based), respecting width and alignment.
+pub(crate) fn fmt_row_column(f: &mut Formatter<'_>, row: u32, column: u32) -> fmt::Result {
+    fmt_pair(f, row + 1, column + 1)
+}
+
+fn fmt_pair(f: &mut Formatter<'_>, first: u32, second: u32) -> fmt::Result {
+    if f.width().is_some() {
+        f.pad(&format!("{}:{}", first, second))
+    } else {
+        write!(f, "{}:{}", first, second)
+    }
+}
+
+/// Row-column pair displayed as `row:column` without converting to 1-based.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ZeroBased {
+    pub(crate) row: u32,
+    pub(crate) column: u32,
+}
+
+impl Display for ZeroBased {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt_pair(f, self.row, self.column)
+    }
+}
+
+/// Add fields of positions.
+///
+/// With `strict-overflow` feature, panic on overflow in release builds too.
+#[inline]
+pub(crate) fn add_field(lhs: u32, rhs: u32) -> u32 {
+    if cfg!(feature = "strict-overflow") {
+        lhs.checked_add(rhs).expect("text position overflow")
+    } else {
+        lhs + rhs
+    }
+}
+
+/// Same as `add_field` for `u64`.
+#[cfg(feature = "index64")]
+#[inline]
+pub(crate) fn add_field64(lhs: u64, rhs: u64) -> u64 {
+    if cfg!(feature = "strict-overflow") {
+        lhs.checked_add(rhs).expect("text position overflow")
+    } else {
+        lhs + rhs
+    }
+}
+
+/// Split UTF-8 bytes into the number of newlines and the final line.
+pub(crate) fn split_final_line(bytes: &[u8]) -> (u32, &[u8]) {
+    let row = bytes.iter().filter(|&&b| b == b'\n').count();
+    let head = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    (row as u32, &bytes[head..])
+}
+
+/// Count UTF-16 code units to encode a string.
+///
+/// ASCII-only strings, which are common in source code, skip the decoding.
+pub(crate) fn utf16_len_str(s: &str) -> u32 {
+    if s.is_ascii() {
+        s.len() as u32
+    } else {
+        s.encode_utf16().count() as u32
+    }
+}
+
+/// Count UTF-16 code units to encode UTF-8 bytes.
+pub(crate) fn utf16_len(bytes: &[u8]) -> u32 {
+    // Count leading bytes. 4-byte sequences need surrogate pairs.
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80..=0xBF => 0,
+            0xF0..=0xFF => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        final_column_of, row_of, ByteCharPosition, CompositePosition, TextPosition, Utf16Position,
+        Utf8Index, Utf8Position,
+    };
+    use std::fmt::Debug;
+
+    /// Every field of a position, so that checks don't rely on how it compares.
+    trait Fields: TextPosition + Debug {
+        type Key: Debug + Eq;
+
+        fn fields(&self) -> Self::Key;
+    }
+
+    impl Fields for Utf8Index {
+        type Key = u32;
+
+        fn fields(&self) -> u32 {
+            self.index
+        }
+    }
+
+    impl Fields for Utf8Position {
+        type Key = (u32, u32);
+
+        fn fields(&self) -> (u32, u32) {
+            (self.row, self.column)
+        }
+    }
+
+    impl Fields for Utf16Position {
+        type Key = (u32, u32);
+
+        fn fields(&self) -> (u32, u32) {
+            (self.row, self.column)
+        }
+    }
+
+    impl Fields for CompositePosition {
+        type Key = (u32, u32, u32, u32);
+
+        fn fields(&self) -> (u32, u32, u32, u32) {
+            (self.index, self.row, self.column8, self.column16)
+        }
+    }
+
+    impl Fields for ByteCharPosition {
+        type Key = (u32, u32);
+
+        fn fields(&self) -> (u32, u32) {
+            (self.byte_index, self.char_index)
+        }
+    }
+
+    const SAMPLES: &[&str] = &[
+        "",
+        "a",
+        "Hello, world!",
+        "\n",
+        "\r\n",
+        "12345\n1234567\n12345",
+        "いろは\nにほへと",
+        "🐧🐧\n\n🐧",
+    ];
+
+    /// Random strings of ASCII, newlines and multibyte characters, generated from a fixed seed.
+    fn generate_strings() -> Vec<String> {
+        const CHARS: &[char] = &['a', ' ', '\t', '\n', '\r', 'é', 'い', '\u{FEFF}', '🐧'];
+
+        // xorshift64
+        let mut state = 0x2545_F491_4F6C_DD1D_u64;
+        let mut next = move |n: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % n as u64) as usize
+        };
+
+        let mut strings = vec![];
+        for _ in 0..300 {
+            let len = next(24);
+            let mut s = String::new();
+            for _ in 0..len {
+                s.push(CHARS[next(CHARS.len())]);
+            }
+            strings.push(s);
+        }
+        strings
+    }
+
+    /// Check `b + (a - b) == a` for every pair of `a = s` and `b = prefix of s`,
+    /// for the samples and generated strings.
+    fn check_saturating_sub_inverts_add<P: Fields>() {
+        let generated = generate_strings();
+        let strings = SAMPLES
+            .iter()
+            .copied()
+            .chain(generated.iter().map(String::as_str));
+
+        for s in strings {
+            let a = P::from_str(s);
+
+            for (i, _) in s.char_indices().chain(std::iter::once((s.len(), ' '))) {
+                let b = P::from_str(&s[..i]);
+                assert!(a >= b);
+                assert_eq!(
+                    (b.clone() + a.clone().saturating_sub(b.clone())).fields(),
+                    a.fields(),
+                    "{:?}",
+                    s
+                );
+            }
+        }
+    }
+
+    /// Check `from_str(a) + from_str(b) == from_str(a + b)` for every pair of samples.
+    fn check_add_agrees_with_from_str<P: Fields>() {
+        for a in SAMPLES {
+            for b in SAMPLES {
+                let ab = format!("{}{}", a, b);
+                assert_eq!(
+                    (P::from_str(a) + P::from_str(b)).fields(),
+                    P::from_str(&ab).fields(),
+                    "{:?}",
+                    ab
+                );
+            }
+        }
+    }
+
+    /// Check `from_str(a) + from_str(b) == from_str(a + b)`
+    /// for every split of generated strings at char boundaries.
+    fn check_add_agrees_with_from_str_at_boundaries<P: Fields>() {
+        for s in generate_strings() {
+            for (i, _) in s.char_indices().chain(std::iter::once((s.len(), ' '))) {
+                let (a, b) = s.split_at(i);
+                assert_eq!(
+                    (P::from_str(a) + P::from_str(b)).fields(),
+                    P::from_str(&s).fields(),
+                    "{:?}",
+                    (a, b)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_add_agrees_with_from_str_at_boundaries() {
+        check_add_agrees_with_from_str_at_boundaries::<Utf8Index>();
+        check_add_agrees_with_from_str_at_boundaries::<Utf8Position>();
+        check_add_agrees_with_from_str_at_boundaries::<Utf16Position>();
+        check_add_agrees_with_from_str_at_boundaries::<CompositePosition>();
+        check_add_agrees_with_from_str_at_boundaries::<ByteCharPosition>();
+    }
+
+    #[test]
+    fn test_add_agrees_with_from_str_utf8_index() {
+        check_add_agrees_with_from_str::<Utf8Index>();
+    }
+
+    #[test]
+    fn test_add_agrees_with_from_str_utf8_position() {
+        check_add_agrees_with_from_str::<Utf8Position>();
+    }
+
+    #[test]
+    fn test_add_agrees_with_from_str_utf16_position() {
+        check_add_agrees_with_from_str::<Utf16Position>();
+    }
+
+    #[test]
+    fn test_add_agrees_with_from_str_composite_position() {
+        check_add_agrees_with_from_str::<CompositePosition>();
+    }
+
+    #[test]
+    fn test_add_agrees_with_from_str_byte_char_position() {
+        check_add_agrees_with_from_str::<ByteCharPosition>();
+    }
+
+    fn check_from_bytes_agrees_with_from_str<P: Fields>() {
+        for s in SAMPLES {
+            assert_eq!(
+                P::from_bytes(s.as_bytes()).fields(),
+                P::from_str(s).fields(),
+                "{:?}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_bytes_agrees_with_from_str() {
+        check_from_bytes_agrees_with_from_str::<Utf8Index>();
+        check_from_bytes_agrees_with_from_str::<Utf8Position>();
+        check_from_bytes_agrees_with_from_str::<Utf16Position>();
+        check_from_bytes_agrees_with_from_str::<CompositePosition>();
+        check_from_bytes_agrees_with_from_str::<ByteCharPosition>();
+    }
+
+    /// Check that measuring chunks split at any two points agrees with measuring the whole.
+    fn check_from_chunks_agrees_with_from_str<P: Fields>() {
+        for s in SAMPLES {
+            let boundaries = s
+                .char_indices()
+                .map(|(i, _)| i)
+                .chain(std::iter::once(s.len()))
+                .collect::<Vec<_>>();
+
+            for &i in &boundaries {
+                for &j in boundaries.iter().filter(|&&j| i <= j) {
+                    let chunks = vec![&s[..i], &s[i..j], &s[j..]];
+                    assert_eq!(
+                        P::from_chunks(chunks).fields(),
+                        P::from_str(s).fields(),
+                        "{:?}",
+                        (s, i, j)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_chunks_agrees_with_from_str() {
+        check_from_chunks_agrees_with_from_str::<Utf8Index>();
+        check_from_chunks_agrees_with_from_str::<Utf8Position>();
+        check_from_chunks_agrees_with_from_str::<Utf16Position>();
+        check_from_chunks_agrees_with_from_str::<CompositePosition>();
+        check_from_chunks_agrees_with_from_str::<ByteCharPosition>();
+    }
+
+    #[test]
+    fn test_saturating_sub_inverts_add_utf8_index() {
+        check_saturating_sub_inverts_add::<Utf8Index>();
+    }
+
+    #[test]
+    fn test_saturating_sub_inverts_add_utf8_position() {
+        check_saturating_sub_inverts_add::<Utf8Position>();
+    }
+
+    #[test]
+    fn test_saturating_sub_inverts_add_utf16_position() {
+        check_saturating_sub_inverts_add::<Utf16Position>();
+    }
+
+    #[test]
+    fn test_saturating_sub_inverts_add_composite_position() {
+        check_saturating_sub_inverts_add::<CompositePosition>();
+    }
+
+    #[test]
+    fn test_saturating_sub_inverts_add_byte_char_position() {
+        check_saturating_sub_inverts_add::<ByteCharPosition>();
+    }
+
+    /// Check that `checked_add` and `saturating_add` agree with `+` unless overflow.
+    fn check_checked_add_agrees_with_add<P: Fields>() {
+        for a in SAMPLES {
+            for b in SAMPLES {
+                let (a, b) = (P::from_str(a), P::from_str(b));
+                let sum = a.clone() + b.clone();
+                assert_eq!(
+                    a.clone().checked_add(b.clone()).map(|p| p.fields()),
+                    Some(sum.fields())
+                );
+                assert_eq!(a.saturating_add(b).fields(), sum.fields());
+            }
+        }
+    }
+
+    #[test]
+    fn test_checked_add_agrees_with_add() {
+        check_checked_add_agrees_with_add::<Utf8Index>();
+        check_checked_add_agrees_with_add::<Utf8Position>();
+        check_checked_add_agrees_with_add::<Utf16Position>();
+        check_checked_add_agrees_with_add::<CompositePosition>();
+        check_checked_add_agrees_with_add::<ByteCharPosition>();
+    }
+
+    /// Check that `join` is a concatenation of changes and associative for every triple of samples.
+    fn check_join<P: Fields>() {
+        let base = "base\n";
+        let at = |s: &str| P::from_str(&format!("{}{}", base, s));
+        let p = P::from_str(base);
+
+        for a in SAMPLES {
+            assert_eq!(
+                P::join(p.clone(), p.clone(), at(a)).fields(),
+                at(a).fields()
+            );
+            assert_eq!(
+                P::join(p.clone(), at(a), p.clone()).fields(),
+                at(a).fields()
+            );
+
+            for b in SAMPLES {
+                let ab = format!("{}{}", a, b);
+                assert_eq!(
+                    P::join(p.clone(), at(a), at(b)).fields(),
+                    at(&ab).fields(),
+                    "{:?}",
+                    ab
+                );
+
+                for c in SAMPLES {
+                    assert_eq!(
+                        P::join(p.clone(), P::join(p.clone(), at(a), at(b)), at(c)).fields(),
+                        P::join(p.clone(), at(a), P::join(p.clone(), at(b), at(c))).fields(),
+                        "{:?}",
+                        (a, b, c)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_join() {
+        check_join::<Utf8Index>();
+        check_join::<Utf8Position>();
+        check_join::<Utf16Position>();
+        check_join::<CompositePosition>();
+        check_join::<ByteCharPosition>();
+    }
+
+    /// Position that implements only the required items, to test the default methods.
+    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Chars(u32);
+
+    impl std::ops::Add for Chars {
+        type Output = Self;
+
+        fn add(self, rhs: Self) -> Self {
+            Chars(self.0 + rhs.0)
+        }
+    }
+
+    impl TextPosition for Chars {
+        const ZERO: Self = Chars(0);
+
+        const MAX: Self = Chars(u32::MAX);
+
+        fn from_str(s: &str) -> Self {
+            Chars(s.chars().count() as u32)
+        }
+
+        fn saturating_sub(self, rhs: Self) -> Self {
+            Chars(self.0.saturating_sub(rhs.0))
+        }
+    }
+
+    #[test]
+    fn test_default_from_bytes_invalid() {
+        assert_eq!(Chars::from_bytes(b"a\xFF\xE3\x81\x84"), Chars(3));
+    }
+
+    #[test]
+    fn test_default_checked_add_and_saturating_add() {
+        assert_eq!(Chars(1).checked_add(Chars(2)), Some(Chars(3)));
+        assert_eq!(Chars(1).checked_add(Chars(u32::MAX - 1)), Some(Chars::MAX));
+        assert_eq!(Chars(2).checked_add(Chars(u32::MAX - 1)), None);
+        assert_eq!(Chars(1).saturating_add(Chars(2)), Chars(3));
+        assert_eq!(Chars(2).saturating_add(Chars(u32::MAX - 1)), Chars::MAX);
+    }
+
+    #[test]
+    fn test_from_bytes_lossy() {
+        let bytes = b"\xFF\n\xE3\x81\x84\xFF\xE3\x81";
+        assert_eq!(Utf8Index::from_bytes_lossy(bytes), Utf8Index::new(8));
+        assert_eq!(
+            Utf8Position::from_bytes_lossy(bytes),
+            Utf8Position::new(1, 6)
+        );
+        assert_eq!(
+            Utf16Position::from_bytes_lossy(bytes),
+            Utf16Position::new(1, 4)
+        );
+        let pos = ByteCharPosition::from_bytes_lossy(bytes);
+        assert_eq!((pos.byte_index, pos.char_index), (8, 6));
+    }
+
+    fn check_from_bytes_lossy_agrees_with_from_str<P: Fields>() {
+        for s in SAMPLES {
+            assert_eq!(
+                P::from_bytes_lossy(s.as_bytes()).fields(),
+                P::from_str(s).fields(),
+                "{:?}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_bytes_lossy_agrees_with_from_str() {
+        check_from_bytes_lossy_agrees_with_from_str::<Utf8Index>();
+        check_from_bytes_lossy_agrees_with_from_str::<Utf8Position>();
+        check_from_bytes_lossy_agrees_with_from_str::<Utf16Position>();
+        check_from_bytes_lossy_agrees_with_from_str::<CompositePosition>();
+        check_from_bytes_lossy_agrees_with_from_str::<ByteCharPosition>();
+    }
+
+    #[test]
+    fn test_clamp_to_text() {
+        let text = "12345\n1234567\n12345";
+        assert_eq!(
+            Utf8Position::new(9, 9).clamp_to_text(text),
+            Utf8Position::new(2, 5)
+        );
+        assert_eq!(
+            Utf8Position::new(1, 2).clamp_to_text(text),
+            Utf8Position::new(1, 2)
+        );
+        assert_eq!(Utf8Index::new(99).clamp_to_text(text), Utf8Index::new(19));
+    }
+
+    #[test]
+    fn test_max_is_identity_of_min() {
+        let positions = vec![Utf8Position::new(3, 1), Utf8Position::new(1, 9)];
+        let min = positions.into_iter().fold(Utf8Position::MAX, Ord::min);
+        assert_eq!(min, Utf8Position::new(1, 9));
+
+        let max = CompositePosition::MAX;
+        assert!(CompositePosition::from_str("いろは") < max);
+    }
+
+    #[test]
+    fn test_advance_agrees_with_from_char() {
+        for s in SAMPLES {
+            let mut index = Utf8Index::ZERO;
+            let mut pos8 = Utf8Position::ZERO;
+            let mut pos16 = Utf16Position::ZERO;
+            let mut composite = CompositePosition::ZERO;
+
+            for c in s.chars() {
+                index = index.advance(c);
+                pos8 = pos8.advance(c);
+                pos16 = pos16.advance(c);
+                composite = composite.advance(c);
+            }
+
+            assert_eq!(index, Utf8Index::from_str(s));
+            assert_eq!(pos8, Utf8Position::from_str(s));
+            assert_eq!(pos16, Utf16Position::from_str(s));
+            assert_eq!(composite, CompositePosition::from_str(s));
+        }
+    }
+
+    #[test]
+    fn test_row_of_and_final_column_of_agree_with_from_str() {
+        for s in SAMPLES {
+            let pos = Utf8Position::from_str(s);
+            assert_eq!(row_of(s), pos.row, "{:?}", s);
+            assert_eq!(final_column_of(s), pos.column, "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn test_byte_len() {
+        let s = "いろは\n🐧";
+        assert_eq!(Utf8Index::from_str(s).byte_len(), Some(14));
+        assert_eq!(CompositePosition::from_str(s).byte_len(), Some(14));
+        assert_eq!(Utf8Position::from_str(s).byte_len(), None);
+        assert_eq!(Utf16Position::from_str(s).byte_len(), None);
+    }
+
+    #[test]
+    fn test_saturating_sub_inverts_add_in_number() {
+        for r1 in 0..4 {
+            for c1 in 0..4 {
+                for r2 in 0..4 {
+                    for c2 in 0..4 {
+                        let a = Utf8Position::new(r1, c1);
+                        let b = Utf8Position::new(r2, c2);
+                        if a >= b {
+                            assert_eq!(b + a.saturating_sub(b), a);
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,219 @@
+// LICENSE: CC0-1.0
+
+use crate::{position::add_field, TextPosition, Utf8Index};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    hash::{Hash, Hasher},
+    ops::{Add, AddAssign},
+};
+
+/// Text position represented by both UTF-8 index and number of characters (Unicode scalar values).
+///
+/// Lighter version of `CompositePosition` without rows and columns,
+/// e.g. to bridge APIs using char offsets and APIs using byte offsets.
+///
+/// Compared and hashed by `byte_index` only. Both start from 0.
+///
+/// ```
+/// use text_position_rs::{ByteCharPosition, TextPosition};
+///
+/// assert_eq!(
+///     ByteCharPosition::from_str("aいb"),
+///     ByteCharPosition::new(5, 3)
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ByteCharPosition {
+    /// Index to UTF-8 string.
+    pub byte_index: u32,
+
+    /// Number of characters.
+    pub char_index: u32,
+}
+
+impl ByteCharPosition {
+    pub const fn new(byte_index: u32, char_index: u32) -> Self {
+        Self {
+            byte_index,
+            char_index,
+        }
+    }
+
+    /// Advance by a character. Same as `self + ByteCharPosition::from(c)`.
+    pub fn advance(self, c: char) -> Self {
+        Self {
+            byte_index: add_field(self.byte_index, c.len_utf8() as u32),
+            char_index: add_field(self.char_index, 1),
+        }
+    }
+}
+
+impl TextPosition for ByteCharPosition {
+    const ZERO: Self = Self::new(0, 0);
+
+    const MAX: Self = Self::new(u32::MAX, u32::MAX);
+
+    fn from_str(s: &str) -> Self {
+        Self {
+            byte_index: s.len() as u32,
+            char_index: s.chars().count() as u32,
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            byte_index: bytes.len() as u32,
+            char_index: bytes
+                .iter()
+                .filter(|&&b| !(0x80..=0xBF).contains(&b))
+                .count() as u32,
+        }
+    }
+
+    fn saturating_sub(self, rhs: Self) -> Self {
+        if self <= rhs {
+            return Self::ZERO;
+        }
+
+        Self {
+            byte_index: self.byte_index - rhs.byte_index,
+            char_index: self.char_index.saturating_sub(rhs.char_index),
+        }
+    }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(Self {
+            byte_index: self.byte_index.checked_add(rhs.byte_index)?,
+            char_index: self.char_index.checked_add(rhs.char_index)?,
+        })
+    }
+
+    fn saturating_add(self, rhs: Self) -> Self {
+        Self {
+            byte_index: self.byte_index.saturating_add(rhs.byte_index),
+            char_index: self.char_index.saturating_add(rhs.char_index),
+        }
+    }
+
+    fn byte_len(&self) -> Option<u32> {
+        Some(self.byte_index)
+    }
+}
+
+impl AddAssign for ByteCharPosition {
+    fn add_assign(&mut self, rhs: Self) {
+        let sum = *self + rhs;
+        *self = sum;
+    }
+}
+
+impl Add for ByteCharPosition {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            byte_index: add_field(self.byte_index, rhs.byte_index),
+            char_index: add_field(self.char_index, rhs.char_index),
+        }
+    }
+}
+
+impl From<char> for ByteCharPosition {
+    fn from(c: char) -> Self {
+        Self {
+            byte_index: c.len_utf8() as u32,
+            char_index: 1,
+        }
+    }
+}
+
+impl From<&'_ str> for ByteCharPosition {
+    fn from(s: &str) -> Self {
+        Self::from_str(s)
+    }
+}
+
+impl From<ByteCharPosition> for Utf8Index {
+    fn from(pos: ByteCharPosition) -> Utf8Index {
+        Utf8Index::new(pos.byte_index)
+    }
+}
+
+impl PartialEq for ByteCharPosition {
+    fn eq(&self, other: &Self) -> bool {
+        self.byte_index == other.byte_index
+    }
+}
+
+impl Eq for ByteCharPosition {}
+
+impl PartialOrd for ByteCharPosition {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ByteCharPosition {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.byte_index.cmp(&other.byte_index)
+    }
+}
+
+impl Hash for ByteCharPosition {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.byte_index.hash(state)
+    }
+}
+
+/// Display as the byte index, same as `Utf8Index`.
+impl Display for ByteCharPosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.byte_index, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ByteCharPosition, TextPosition};
+
+    fn pos_of(s: &str) -> ByteCharPosition {
+        ByteCharPosition::from_str(s)
+    }
+
+    #[test]
+    fn test_from_str() {
+        let pos = pos_of("いろは\n🐧");
+        assert_eq!((pos.byte_index, pos.char_index), (14, 5));
+    }
+
+    #[test]
+    fn test_saturating_sub() {
+        let diff = pos_of("aいb🐧").saturating_sub(pos_of("aい"));
+        assert_eq!((diff.byte_index, diff.char_index), (5, 2));
+        assert_eq!(
+            pos_of("a").saturating_sub(pos_of("aい")),
+            ByteCharPosition::ZERO
+        );
+    }
+
+    #[test]
+    fn test_add() {
+        let pos = pos_of("aい") + pos_of("b🐧");
+        assert_eq!((pos.byte_index, pos.char_index), (9, 4));
+    }
+
+    #[test]
+    fn test_join() {
+        let base = pos_of("い");
+        let pos = ByteCharPosition::join(base, pos_of("いろ"), pos_of("い🐧"));
+        assert_eq!((pos.byte_index, pos.char_index), (10, 3));
+    }
+
+    #[test]
+    fn test_from_chunks() {
+        let pos = ByteCharPosition::from_chunks(vec!["aい", "", "🐧\n"]);
+        assert_eq!((pos.byte_index, pos.char_index), (9, 4));
+    }
+}
@@ -1,8 +1,12 @@
 // LICENSE: CC0-1.0
 
-use crate::{TextPosition, Utf16Position, Utf8Index, Utf8Position};
+use crate::{
+    position::{add_field, fmt_row_column, split_final_line, utf16_len, utf16_len_str, ZeroBased},
+    PositionError, TextPosition, Utf16Position, Utf8Index, Utf8Position,
+};
 use std::{
     cmp::Ordering,
+    convert::TryFrom,
     fmt::{self, Display, Formatter},
     hash::{Hash, Hasher},
     ops::{Add, AddAssign},
@@ -17,6 +21,8 @@ use std::{
 ///
 /// All of them start from 0.
 #[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "CompositePositionRepr"))]
 pub struct CompositePosition {
     /// UTF-8 index.
     pub index: u32,
@@ -40,6 +46,66 @@ impl CompositePosition {
             column16,
         }
     }
+
+    /// Display as 0-based `row:column8`, unlike `Display` which is 1-based.
+    ///
+    /// ```
+    /// use text_position_rs::CompositePosition;
+    ///
+    /// let pos = CompositePosition::new(40, 3, 1, 1);
+    /// assert_eq!(format!("{}", pos), "4:2");
+    /// assert_eq!(format!("{}", pos.display_zero_based()), "3:1");
+    /// ```
+    pub fn display_zero_based(self) -> impl Display {
+        ZeroBased {
+            row: self.row,
+            column: self.column8,
+        }
+    }
+
+    /// Advance by a character. Same as `self + CompositePosition::from(c)`.
+    pub fn advance(self, c: char) -> Self {
+        let index = add_field(self.index, c.len_utf8() as u32);
+
+        if c == '\n' {
+            Self {
+                index,
+                row: add_field(self.row, 1),
+                column8: 0,
+                column16: 0,
+            }
+        } else {
+            Self {
+                index,
+                row: self.row,
+                column8: add_field(self.column8, c.len_utf8() as u32),
+                column16: add_field(self.column16, c.len_utf16() as u32),
+            }
+        }
+    }
+
+    /// Calculate the position at a UTF-8 index in the text.
+    ///
+    /// The index is clamped to the end of text
+    /// and moved back to the start of a character if it's in the middle of that.
+    ///
+    /// ```
+    /// use text_position_rs::{CompositePosition, Utf8Index};
+    ///
+    /// let text = "ab\n🐧";
+    /// assert_eq!(
+    ///     CompositePosition::resolve(text, Utf8Index::new(4)),
+    ///     CompositePosition::new(3, 1, 0, 0)
+    /// );
+    /// // Out of bounds.
+    /// assert_eq!(
+    ///     CompositePosition::resolve(text, Utf8Index::new(99)),
+    ///     CompositePosition::new(7, 1, 4, 2)
+    /// );
+    /// ```
+    pub fn resolve(text: &str, index: Utf8Index) -> Self {
+        Self::from_prefix_len(index.index as usize, text)
+    }
 }
 
 impl TextPosition for CompositePosition {
@@ -50,6 +116,13 @@ impl TextPosition for CompositePosition {
         column16: 0,
     };
 
+    const MAX: Self = Self {
+        index: u32::MAX,
+        row: u32::MAX,
+        column8: u32::MAX,
+        column16: u32::MAX,
+    };
+
     fn from_str(s: &str) -> Self {
         let mut row = 0;
         let mut head = 0;
@@ -63,7 +136,17 @@ impl TextPosition for CompositePosition {
             index: s.len() as u32,
             row: row as u32,
             column8: (s.len() - head) as u32,
-            column16: s[head..].encode_utf16().count() as u32,
+            column16: utf16_len_str(&s[head..]),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let (row, final_line) = split_final_line(bytes);
+        Self {
+            index: bytes.len() as u32,
+            row,
+            column8: final_line.len() as u32,
+            column16: utf16_len(final_line),
         }
     }
 
@@ -84,6 +167,50 @@ impl TextPosition for CompositePosition {
             },
         }
     }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        let index = self.index.checked_add(rhs.index)?;
+
+        if rhs.row == 0 {
+            Some(Self {
+                index,
+                row: self.row,
+                column8: self.column8.checked_add(rhs.column8)?,
+                column16: self.column16.checked_add(rhs.column16)?,
+            })
+        } else {
+            Some(Self {
+                index,
+                row: self.row.checked_add(rhs.row)?,
+                column8: rhs.column8,
+                column16: rhs.column16,
+            })
+        }
+    }
+
+    fn saturating_add(self, rhs: Self) -> Self {
+        let index = self.index.saturating_add(rhs.index);
+
+        if rhs.row == 0 {
+            Self {
+                index,
+                row: self.row,
+                column8: self.column8.saturating_add(rhs.column8),
+                column16: self.column16.saturating_add(rhs.column16),
+            }
+        } else {
+            Self {
+                index,
+                row: self.row.saturating_add(rhs.row),
+                column8: rhs.column8,
+                column16: rhs.column16,
+            }
+        }
+    }
+
+    fn byte_len(&self) -> Option<u32> {
+        Some(self.index)
+    }
 }
 
 impl From<char> for CompositePosition {
@@ -124,19 +251,19 @@ impl Add for CompositePosition {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self {
-        let index = self.index + rhs.index;
+        let index = add_field(self.index, rhs.index);
 
         if rhs.row == 0 {
             Self {
                 index,
                 row: self.row,
-                column8: self.column8 + rhs.column8,
-                column16: self.column16 + rhs.column16,
+                column8: add_field(self.column8, rhs.column8),
+                column16: add_field(self.column16, rhs.column16),
             }
         } else {
             Self {
                 index,
-                row: self.row + rhs.row,
+                row: add_field(self.row, rhs.row),
                 column8: rhs.column8,
                 column16: rhs.column16,
             }
@@ -144,6 +271,72 @@ impl Add for CompositePosition {
     }
 }
 
+impl From<CompositePosition> for (u32, u32, u32, u32) {
+    /// Convert to a tuple of `(index, row, column8, column16)`.
+    fn from(pos: CompositePosition) -> (u32, u32, u32, u32) {
+        (pos.index, pos.row, pos.column8, pos.column16)
+    }
+}
+
+impl TryFrom<(u32, u32, u32, u32)> for CompositePosition {
+    type Error = PositionError;
+
+    /// Convert from a tuple of `(index, row, column8, column16)`.
+    ///
+    /// Fail if the measures are obviously inconsistent,
+    /// e.g. the index is less than the number of bytes in the final line.
+    /// `MAX` is accepted as a sentinel.
+    fn try_from(tuple: (u32, u32, u32, u32)) -> Result<Self, Self::Error> {
+        if tuple == CompositePosition::MAX.into() {
+            return Ok(CompositePosition::MAX);
+        }
+
+        let (index, row, column8, column16) = tuple;
+
+        // Every newline takes a byte. A character takes 1 to 3 bytes per UTF-16 code unit.
+        let consistent = (index as u64) >= (row as u64) + (column8 as u64)
+            && (row != 0 || index == column8)
+            && column16 <= column8
+            && (column8 as u64) <= 3 * (column16 as u64);
+        if !consistent {
+            return Err(PositionError::Inconsistent(tuple));
+        }
+
+        Ok(Self::new(index, row, column8, column16))
+    }
+}
+
+/// Fields of `CompositePosition` deserialized before the consistency check.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(rename = "CompositePosition")]
+struct CompositePositionRepr {
+    index: u32,
+    row: u32,
+    column8: u32,
+    column16: u32,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<CompositePositionRepr> for CompositePosition {
+    type Error = PositionError;
+
+    fn try_from(repr: CompositePositionRepr) -> Result<Self, Self::Error> {
+        Self::try_from((repr.index, repr.row, repr.column8, repr.column16))
+    }
+}
+
+/// UTF-8 index as `usize`, e.g. to slice the text.
+impl From<CompositePosition> for usize {
+    fn from(pos: CompositePosition) -> usize {
+        pos.index as usize
+    }
+}
+
+// Projections below preserve ordering (and equality)
+// as long as the positions are of the same text.
+// The `checked` feature verifies it on each comparison of `CompositePosition`s.
+
 impl From<CompositePosition> for Utf8Index {
     fn from(pos: CompositePosition) -> Self {
         Utf8Index::new(pos.index)
@@ -240,39 +433,58 @@ impl PartialOrd<CompositePosition> for Utf16Position {
     }
 }
 
+/// Panic, or log a warning with `checked-log` feature.
+#[allow(unused)]
+fn report_inconsistency(it: &CompositePosition, other: &CompositePosition, relation: &str) {
+    #[cfg(feature = "checked-log")]
+    log::warn!(
+        "inconsistent CompositePosition: {:?} {} {:?} by index but not by projections",
+        it,
+        relation,
+        other
+    );
+
+    #[cfg(not(feature = "checked-log"))]
+    panic!(
+        "inconsistent CompositePosition: {:?} {} {:?} by index but not by projections",
+        it, relation, other
+    );
+}
+
 #[allow(unused)]
-fn assert_equality_consistency(it: &CompositePosition, other: &CompositePosition, equal: bool) {
-    if equal {
-        assert_eq!(Utf8Position::from(*it), Utf8Position::from(*other));
-        assert_eq!(Utf16Position::from(*it), Utf16Position::from(*other));
-    } else {
-        assert_ne!(Utf8Position::from(*it), Utf8Position::from(*other));
-        assert_ne!(Utf16Position::from(*it), Utf16Position::from(*other));
+fn check_equality_consistency(it: &CompositePosition, other: &CompositePosition, equal: bool) {
+    let equal8 = Utf8Position::from(*it) == Utf8Position::from(*other);
+    let equal16 = Utf16Position::from(*it) == Utf16Position::from(*other);
+    if equal8 != equal || equal16 != equal {
+        report_inconsistency(it, other, if equal { "==" } else { "!=" });
     }
 }
 
 #[allow(unused)]
-fn assert_ordering_consistency(
+fn check_ordering_consistency(
     it: &CompositePosition,
     other: &CompositePosition,
     ordering: Option<Ordering>,
 ) {
-    assert_eq!(
-        Utf8Position::from(*it).partial_cmp(&Utf8Position::from(*other)),
-        ordering
-    );
-    assert_eq!(
-        Utf16Position::from(*it).partial_cmp(&Utf16Position::from(*other)),
-        ordering
-    );
+    let ordering8 = Utf8Position::from(*it).partial_cmp(&Utf8Position::from(*other));
+    let ordering16 = Utf16Position::from(*it).partial_cmp(&Utf16Position::from(*other));
+    if ordering8 != ordering || ordering16 != ordering {
+        let relation = match ordering {
+            Some(Ordering::Less) => "<",
+            Some(Ordering::Equal) => "==",
+            Some(Ordering::Greater) => ">",
+            None => "<>",
+        };
+        report_inconsistency(it, other, relation);
+    }
 }
 
 impl PartialEq for CompositePosition {
     fn eq(&self, other: &Self) -> bool {
         let equal = self.index == other.index;
 
-        #[cfg(feature = "checked")]
-        assert_equality_consistency(self, other, equal);
+        #[cfg(any(feature = "checked", feature = "checked-log"))]
+        check_equality_consistency(self, other, equal);
 
         equal
     }
@@ -280,12 +492,13 @@ impl PartialEq for CompositePosition {
 
 impl Eq for CompositePosition {}
 
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl PartialOrd for CompositePosition {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         let ordering = self.index.partial_cmp(&other.index);
 
-        #[cfg(feature = "checked")]
-        assert_ordering_consistency(self, other, ordering);
+        #[cfg(any(feature = "checked", feature = "checked-log"))]
+        check_ordering_consistency(self, other, ordering);
 
         ordering
     }
@@ -295,16 +508,37 @@ impl Ord for CompositePosition {
     fn cmp(&self, other: &Self) -> Ordering {
         let ordering = self.index.cmp(&other.index);
 
-        #[cfg(feature = "checked")]
-        assert_ordering_consistency(self, other, Some(ordering));
+        #[cfg(any(feature = "checked", feature = "checked-log"))]
+        check_ordering_consistency(self, other, Some(ordering));
 
         ordering
     }
 }
 
+/// Display as 1-based `row:column8`.
+/// The alternate form (`{:#}`) also shows the index and the 1-based `column16`.
+///
+/// ```
+/// use text_position_rs::CompositePosition;
+///
+/// let pos = CompositePosition::new(72, 3, 15, 14);
+/// assert_eq!(format!("{}", pos), "4:16");
+/// assert_eq!(format!("{:#}", pos), "idx=72 4:16 (u16 15)");
+/// ```
 impl Display for CompositePosition {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}:{}", self.row + 1, self.column8 + 1)
+        if f.alternate() {
+            return write!(
+                f,
+                "idx={} {}:{} (u16 {})",
+                self.index,
+                self.row + 1,
+                self.column8 + 1,
+                self.column16 + 1
+            );
+        }
+
+        fmt_row_column(f, self.row, self.column8)
     }
 }
 
@@ -314,9 +548,49 @@ impl Hash for CompositePosition {
     }
 }
 
+/// Wrapper of `CompositePosition` compared and hashed by all fields,
+/// unlike `CompositePosition` itself which is compared by index only.
+///
+/// Useful as a key of map to distinguish positions with the same index but different rows or columns.
+/// Comparison of keys doesn't run the consistency checks of the `checked` feature.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct CompositePositionFullKey(pub CompositePosition);
+
+impl CompositePositionFullKey {
+    fn fields(&self) -> (u32, u32, u32, u32) {
+        let CompositePosition {
+            index,
+            row,
+            column8,
+            column16,
+        } = self.0;
+        (index, row, column8, column16)
+    }
+}
+
+impl From<CompositePosition> for CompositePositionFullKey {
+    fn from(pos: CompositePosition) -> Self {
+        Self(pos)
+    }
+}
+
+impl PartialEq for CompositePositionFullKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.fields() == other.fields()
+    }
+}
+
+impl Eq for CompositePositionFullKey {}
+
+impl Hash for CompositePositionFullKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.fields().hash(state)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{position::TextPosition, CompositePosition};
+    use crate::{position::TextPosition, CompositePosition, Utf8Index};
 
     const ZERO: CompositePosition = CompositePosition::ZERO;
 
@@ -347,10 +621,10 @@ mod tests {
 
     #[test]
     fn test_from_str_unicode() {
-        assert_eq!(
-            pos_of("いろはにほへと\nちりぬるを\nわかよたれそ\nつねならむ"),
-            CompositePosition::new(72, 3, 15, 15)
-        );
+        // The final line has 5 characters of 3 bytes in UTF-8 and 1 code unit in UTF-16.
+        // Compare all fields since equality only sees the index.
+        let pos = pos_of("いろはにほへと\nちりぬるを\nわかよたれそ\nつねならむ");
+        assert_eq!(<(u32, u32, u32, u32)>::from(pos), (72, 3, 15, 5));
     }
 
     #[test]
@@ -363,6 +637,61 @@ mod tests {
         assert_eq!(pos_of("\r\n"), CompositePosition::new(2, 1, 0, 0));
     }
 
+    #[test]
+    fn test_from_str_ref() {
+        let s = "いろは\n🐧";
+        assert_eq!(CompositePosition::from(s), pos_of(s));
+    }
+
+    #[test]
+    fn test_resolve_in_middle_of_char() {
+        assert_eq!(
+            CompositePosition::resolve("a🐧b", Utf8Index::new(3)),
+            pos_of("a")
+        );
+    }
+
+    #[test]
+    fn test_into_usize() {
+        let text = "いろは\nにほへと";
+        let pos = pos_of("いろは\nに");
+        assert_eq!(&text[usize::from(pos)..], "ほへと");
+    }
+
+    #[test]
+    fn test_tuple_round_trip() {
+        use std::convert::TryFrom;
+
+        let pos = pos_of("いろは\n🐧x");
+        let tuple: (u32, u32, u32, u32) = pos.into();
+        assert_eq!(tuple, (15, 1, 5, 3));
+        assert_eq!(CompositePosition::try_from(tuple), Ok(pos));
+
+        let max = CompositePosition::MAX;
+        assert_eq!(
+            CompositePosition::try_from(<(u32, u32, u32, u32)>::from(max)),
+            Ok(max)
+        );
+    }
+
+    #[test]
+    fn test_tuple_inconsistent() {
+        use crate::PositionError;
+        use std::convert::TryFrom;
+
+        for &tuple in &[(4, 1, 4, 4), (4, 0, 3, 3), (4, 0, 4, 5), (4, 0, 4, 1)] {
+            assert_eq!(
+                CompositePosition::try_from(tuple),
+                Err(PositionError::Inconsistent(tuple))
+            );
+        }
+
+        assert_eq!(
+            PositionError::Inconsistent((4, 1, 4, 4)).to_string(),
+            "inconsistent position (index=4, row=1, column8=4, column16=4)"
+        );
+    }
+
     #[test]
     fn test_add_single_line() {
         assert_eq!(
@@ -422,6 +751,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_compare_to_utf8_index() {
+        let pos = pos_of("12\n34");
+        assert_eq!(pos, Utf8Index::new(5));
+        assert_eq!(Utf8Index::new(5), pos);
+        assert!(pos < Utf8Index::new(6));
+        assert!(Utf8Index::new(4) < pos);
+    }
+
+    #[cfg(all(feature = "checked", not(feature = "checked-log")))]
+    #[test]
+    #[should_panic(expected = "inconsistent CompositePosition")]
+    fn test_checked_panics_on_inconsistency() {
+        let _ = CompositePosition::new(8, 1, 3, 3) == CompositePosition::new(8, 2, 0, 0);
+    }
+
+    // The checked-log feature only logs the inconsistency and compares by index as usual.
+    #[cfg(feature = "checked-log")]
+    #[test]
+    fn test_checked_log_returns_on_inconsistency() {
+        assert_eq!(
+            CompositePosition::new(8, 1, 3, 3),
+            CompositePosition::new(8, 2, 0, 0)
+        );
+    }
+
+    // The checked feature rejects comparison of such inconsistent positions.
+    #[cfg(not(feature = "checked"))]
+    #[test]
+    fn test_hash_consistent_with_eq() {
+        use std::{
+            collections::hash_map::DefaultHasher,
+            hash::{Hash, Hasher},
+        };
+
+        fn hash_of(pos: CompositePosition) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            pos.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let first = CompositePosition::new(8, 1, 3, 3);
+        let second = CompositePosition::new(8, 2, 0, 0);
+        assert_eq!(first, second);
+        assert_eq!(hash_of(first), hash_of(second));
+    }
+
+    #[test]
+    fn test_full_key_distinguishes_columns() {
+        use crate::CompositePositionFullKey;
+        use std::collections::HashSet;
+
+        let keys: HashSet<_> = vec![
+            CompositePosition::new(4, 0, 4, 2),
+            CompositePosition::new(4, 0, 4, 4),
+            CompositePosition::new(4, 0, 4, 2),
+        ]
+        .into_iter()
+        .map(CompositePositionFullKey::from)
+        .collect();
+        assert_eq!(keys.len(), 2);
+    }
+
+    #[test]
+    fn test_projection_preserves_ordering() {
+        use crate::{Utf16Position, Utf8Position};
+
+        let text = "ab🐧\nいろは\r\n\nxyz🐧";
+        let positions = text
+            .char_indices()
+            .map(|(i, _)| pos_of(&text[..i]))
+            .collect::<Vec<_>>();
+
+        for w in positions.windows(2) {
+            assert!(w[0] < w[1]);
+            assert!(Utf8Index::from(w[0]) < Utf8Index::from(w[1]));
+            assert!(Utf8Position::from(w[0]) < Utf8Position::from(w[1]));
+            assert!(Utf16Position::from(w[0]) < Utf16Position::from(w[1]));
+        }
+    }
+
     #[test]
     fn test_display_zero() {
         assert_eq!(format!("{}", ZERO), "1:1");
@@ -1,6 +1,9 @@
 // LICENSE: CC0-1.0
 
-use crate::TextPosition;
+use crate::{
+    position::{add_field, fmt_row_column, split_final_line, utf16_len, utf16_len_str, ZeroBased},
+    TextPosition,
+};
 use std::{
     cmp::Ordering,
     fmt::{self, Debug, Display, Formatter},
@@ -11,6 +14,7 @@ use std::{
 /// Column number (= length of the final line) is measured as number of UTF-16 code units (basically half of bytes).
 /// Start from 0.
 #[derive(Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Utf16Position {
     pub row: u32,
     pub column: u32,
@@ -20,11 +24,74 @@ impl Utf16Position {
     pub const fn new(row: u32, column: u32) -> Self {
         Self { row, column }
     }
+
+    /// Display as 0-based `row:column`, unlike `Display` which is 1-based.
+    ///
+    /// ```
+    /// use text_position_rs::Utf16Position;
+    ///
+    /// let pos = Utf16Position::new(3, 1);
+    /// assert_eq!(format!("{}", pos), "4:2");
+    /// assert_eq!(format!("{}", pos.display_zero_based()), "3:1");
+    /// ```
+    pub fn display_zero_based(self) -> impl Display {
+        ZeroBased {
+            row: self.row,
+            column: self.column,
+        }
+    }
+
+    /// Advance by a character. Same as `self + Utf16Position::from(c)`.
+    pub fn advance(self, c: char) -> Self {
+        if c == '\n' {
+            Self {
+                row: add_field(self.row, 1),
+                column: 0,
+            }
+        } else {
+            Self {
+                row: self.row,
+                column: add_field(self.column, c.len_utf16() as u32),
+            }
+        }
+    }
+
+    /// Whether two positions are on the same line.
+    pub fn same_row(self, other: Self) -> bool {
+        self.row == other.row
+    }
+
+    /// Compare columns of two positions on the same line.
+    ///
+    /// Return `None` if they are on different lines,
+    /// since columns of different lines aren't comparable.
+    pub fn column_cmp(self, other: Self) -> Option<Ordering> {
+        if self.same_row(other) {
+            Some(self.column.cmp(&other.column))
+        } else {
+            None
+        }
+    }
+
+    /// Move left by columns, keeping the row. Column is clamped to 0.
+    pub fn saturating_sub_column(self, columns: u32) -> Self {
+        Self::new(self.row, self.column.saturating_sub(columns))
+    }
+
+    /// Move up by rows, keeping the column. Row is clamped to 0.
+    pub fn saturating_sub_row(self, rows: u32) -> Self {
+        Self::new(self.row.saturating_sub(rows), self.column)
+    }
 }
 
 impl TextPosition for Utf16Position {
     const ZERO: Self = Self { row: 0, column: 0 };
 
+    const MAX: Self = Self {
+        row: u32::MAX,
+        column: u32::MAX,
+    };
+
     fn from_str(s: &str) -> Self {
         let mut row = 0;
         let mut head = 0;
@@ -36,7 +103,15 @@ impl TextPosition for Utf16Position {
 
         Self {
             row: row as u32,
-            column: s[head..].encode_utf16().count() as u32,
+            column: utf16_len_str(&s[head..]),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let (row, final_line) = split_final_line(bytes);
+        Self {
+            row,
+            column: utf16_len(final_line),
         }
     }
 
@@ -53,6 +128,34 @@ impl TextPosition for Utf16Position {
             },
         }
     }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        if rhs.row == 0 {
+            Some(Self {
+                row: self.row,
+                column: self.column.checked_add(rhs.column)?,
+            })
+        } else {
+            Some(Self {
+                row: self.row.checked_add(rhs.row)?,
+                column: rhs.column,
+            })
+        }
+    }
+
+    fn saturating_add(self, rhs: Self) -> Self {
+        if rhs.row == 0 {
+            Self {
+                row: self.row,
+                column: self.column.saturating_add(rhs.column),
+            }
+        } else {
+            Self {
+                row: self.row.saturating_add(rhs.row),
+                column: rhs.column,
+            }
+        }
+    }
 }
 
 impl Add for Utf16Position {
@@ -62,11 +165,11 @@ impl Add for Utf16Position {
         if rhs.row == 0 {
             Self {
                 row: self.row,
-                column: self.column + rhs.column,
+                column: add_field(self.column, rhs.column),
             }
         } else {
             Self {
-                row: self.row + rhs.row,
+                row: add_field(self.row, rhs.row),
                 column: rhs.column,
             }
         }
@@ -99,6 +202,15 @@ impl From<&'_ str> for Utf16Position {
     }
 }
 
+/// Position at the column on the first row, for single-line text.
+///
+/// Row is always 0. Use `new` for multiline text.
+impl From<u32> for Utf16Position {
+    fn from(column: u32) -> Self {
+        Self::new(0, column)
+    }
+}
+
 impl From<Utf16Position> for (u32, u32) {
     fn from(pos: Utf16Position) -> (u32, u32) {
         (pos.row, pos.column)
@@ -113,7 +225,7 @@ impl Debug for Utf16Position {
 
 impl Display for Utf16Position {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}:{}", self.row + 1, self.column + 1)
+        fmt_row_column(f, self.row, self.column)
     }
 }
 
@@ -234,13 +346,43 @@ mod tests {
         assert_eq!(pos_at(4, 6).saturating_sub(pos_at(4, 1)), pos_at(0, 5));
     }
 
+    #[test]
+    fn test_column_cmp() {
+        use std::cmp::Ordering;
+
+        assert!(pos_at(2, 3).same_row(pos_at(2, 8)));
+        assert_eq!(pos_at(2, 3).column_cmp(pos_at(2, 8)), Some(Ordering::Less));
+        assert_eq!(pos_at(2, 3).column_cmp(pos_at(2, 3)), Some(Ordering::Equal));
+        assert_eq!(pos_at(2, 3).column_cmp(pos_at(1, 3)), None);
+    }
+
     #[test]
     fn test_display_zero() {
         assert_eq!(format!("{}", ZERO), "1:1");
     }
 
+    #[test]
+    fn test_display_width() {
+        assert_eq!(format!("{:>8}", pos_at(0, 0)), "     1:1");
+        assert_eq!(format!("{:<8}|", pos_at(9, 9)), "10:10   |");
+    }
+
     #[test]
     fn test_display_nonzero() {
         assert_eq!(format!("{}", pos_at(3, 1)), "4:2");
     }
+
+    #[test]
+    fn test_from_column() {
+        assert_eq!(Utf16Position::from(7), Utf16Position::new(0, 7));
+    }
+
+    #[test]
+    fn test_saturating_sub_axis() {
+        let pos = pos_at(2, 5);
+        assert_eq!(pos.saturating_sub_column(3), pos_at(2, 2));
+        assert_eq!(pos.saturating_sub_column(9), pos_at(2, 0));
+        assert_eq!(pos.saturating_sub_row(1), pos_at(1, 5));
+        assert_eq!(pos.saturating_sub_row(9), pos_at(0, 5));
+    }
 }
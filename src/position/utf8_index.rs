@@ -1,7 +1,8 @@
 // LICENSE: CC0-1.0
 
-use crate::TextPosition;
+use crate::{position::add_field, PositionError, TextPosition};
 use std::{
+    convert::TryFrom,
     fmt::{self, Debug, Display, Formatter},
     ops::{Add, AddAssign},
 };
@@ -9,6 +10,7 @@ use std::{
 /// Text position represented by UTF-8 index.
 /// Start from 0.
 #[derive(Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Utf8Index {
     /// Index to UTF-8 string.
     pub index: u32,
@@ -18,27 +20,108 @@ impl Utf8Index {
     pub const fn new(index: u32) -> Self {
         Self { index }
     }
+
+    /// Move back to the start of the character if the index is in the middle of that.
+    /// The index is also clamped to the end of text.
+    ///
+    /// ```
+    /// use text_position_rs::Utf8Index;
+    ///
+    /// assert_eq!(Utf8Index::new(3).snap_to_char_boundary("a🐧b"), Utf8Index::new(1));
+    /// ```
+    pub fn snap_to_char_boundary(self, text: &str) -> Self {
+        let mut index = (self.index as usize).min(text.len());
+        while !text.is_char_boundary(index) {
+            index -= 1;
+        }
+        Self::new(index as u32)
+    }
+
+    /// Move forward to the end of the character if the index is in the middle of that.
+    /// The index is also clamped to the end of text.
+    ///
+    /// ```
+    /// use text_position_rs::Utf8Index;
+    ///
+    /// assert_eq!(Utf8Index::new(3).snap_to_char_boundary_up("a🐧b"), Utf8Index::new(5));
+    /// ```
+    pub fn snap_to_char_boundary_up(self, text: &str) -> Self {
+        let mut index = (self.index as usize).min(text.len());
+        while !text.is_char_boundary(index) {
+            index += 1;
+        }
+        Self::new(index as u32)
+    }
+
+    /// Addition modulo `modulus`, e.g. for offsets in a ring buffer.
+    ///
+    /// Panic if `modulus` is zero.
+    ///
+    /// ```
+    /// use text_position_rs::Utf8Index;
+    ///
+    /// let modulus = Utf8Index::new(10);
+    /// assert_eq!(
+    ///     Utf8Index::new(7).wrapping_add(Utf8Index::new(5), modulus),
+    ///     Utf8Index::new(2)
+    /// );
+    /// ```
+    pub fn wrapping_add(self, rhs: Self, modulus: Self) -> Self {
+        let sum = self.index as u64 + rhs.index as u64;
+        Self::new((sum % modulus.index as u64) as u32)
+    }
+
+    /// Advance by a character. Same as `self + Utf8Index::from(c)`.
+    pub fn advance(self, c: char) -> Self {
+        Self {
+            index: add_field(self.index, c.len_utf8() as u32),
+        }
+    }
 }
 
 impl TextPosition for Utf8Index {
     const ZERO: Self = Self { index: 0 };
 
+    const MAX: Self = Self { index: u32::MAX };
+
     fn from_str(s: &str) -> Self {
         Self {
             index: s.len() as u32,
         }
     }
 
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            index: bytes.len() as u32,
+        }
+    }
+
     fn saturating_sub(self, rhs: Self) -> Self {
         Self {
             index: self.index.saturating_sub(rhs.index),
         }
     }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(Self {
+            index: self.index.checked_add(rhs.index)?,
+        })
+    }
+
+    fn saturating_add(self, rhs: Self) -> Self {
+        Self {
+            index: self.index.saturating_add(rhs.index),
+        }
+    }
+
+    fn byte_len(&self) -> Option<u32> {
+        Some(self.index)
+    }
 }
 
 impl AddAssign for Utf8Index {
     fn add_assign(&mut self, rhs: Self) {
-        self.index += rhs.index;
+        self.index = add_field(self.index, rhs.index);
     }
 }
 
@@ -47,7 +130,7 @@ impl Add for Utf8Index {
 
     fn add(self, rhs: Self) -> Self {
         Self {
-            index: self.index + rhs.index,
+            index: add_field(self.index, rhs.index),
         }
     }
 }
@@ -58,6 +141,29 @@ impl From<u32> for Utf8Index {
     }
 }
 
+impl TryFrom<usize> for Utf8Index {
+    type Error = PositionError;
+
+    /// Fail if the index exceeds `u32::MAX`.
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        u32::try_from(index)
+            .map(Self::new)
+            .map_err(|_| PositionError::OutOfBounds)
+    }
+}
+
+impl From<Utf8Index> for usize {
+    fn from(index: Utf8Index) -> usize {
+        index.index as usize
+    }
+}
+
+impl From<Utf8Index> for u64 {
+    fn from(index: Utf8Index) -> u64 {
+        index.index as u64
+    }
+}
+
 impl From<char> for Utf8Index {
     fn from(c: char) -> Self {
         Self {
@@ -83,3 +189,66 @@ impl Display for Utf8Index {
         Display::fmt(&self.index, f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{PositionError, Utf8Index};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_snap_to_char_boundary() {
+        let text = "aいb";
+        let snapped = (0..=6)
+            .map(|i| {
+                let index = Utf8Index::new(i);
+                (
+                    index.snap_to_char_boundary(text).index,
+                    index.snap_to_char_boundary_up(text).index,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            snapped,
+            vec![(0, 0), (1, 1), (1, 4), (1, 4), (4, 4), (5, 5), (5, 5)]
+        );
+    }
+
+    #[test]
+    fn test_wrapping_add() {
+        let modulus = Utf8Index::new(u32::MAX);
+        assert_eq!(
+            Utf8Index::new(u32::MAX - 1).wrapping_add(Utf8Index::new(3), modulus),
+            Utf8Index::new(2)
+        );
+        assert_eq!(
+            Utf8Index::new(3).wrapping_add(Utf8Index::new(4), Utf8Index::new(8)),
+            Utf8Index::new(7)
+        );
+    }
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(format!("{:>4}", Utf8Index::new(42)), "  42");
+    }
+
+    #[test]
+    fn test_try_from_usize() {
+        assert_eq!(Utf8Index::try_from(42_usize), Ok(Utf8Index::new(42)));
+        assert_eq!(usize::from(Utf8Index::new(42)), 42);
+        assert_eq!(u64::from(Utf8Index::new(42)), 42);
+    }
+
+    #[test]
+    fn test_try_from_usize_overflow() {
+        if let Some(index) = (u32::MAX as usize).checked_add(1) {
+            assert_eq!(Utf8Index::try_from(index), Err(PositionError::OutOfBounds));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "strict-overflow")]
+    #[should_panic(expected = "text position overflow")]
+    fn test_strict_overflow() {
+        let _ = Utf8Index::new(u32::MAX) + Utf8Index::new(1);
+    }
+}
@@ -0,0 +1,153 @@
+// LICENSE: CC0-1.0
+
+use crate::{position::add_field64, PositionError, TextPosition, Utf8Index};
+use std::{
+    convert::TryFrom,
+    fmt::{self, Debug, Display, Formatter},
+    ops::{Add, AddAssign},
+};
+
+/// Text position represented by UTF-8 index in 64-bit.
+/// Use this instead of `Utf8Index` for text larger than 4 GiB.
+/// Start from 0.
+#[derive(Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Utf8Index64 {
+    /// Index to UTF-8 string.
+    pub index: u64,
+}
+
+impl Utf8Index64 {
+    pub const fn new(index: u64) -> Self {
+        Self { index }
+    }
+}
+
+impl TextPosition for Utf8Index64 {
+    const ZERO: Self = Self { index: 0 };
+
+    const MAX: Self = Self { index: u64::MAX };
+
+    fn from_str(s: &str) -> Self {
+        Self {
+            index: s.len() as u64,
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            index: bytes.len() as u64,
+        }
+    }
+
+    fn saturating_sub(self, rhs: Self) -> Self {
+        Self {
+            index: self.index.saturating_sub(rhs.index),
+        }
+    }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(Self {
+            index: self.index.checked_add(rhs.index)?,
+        })
+    }
+
+    fn saturating_add(self, rhs: Self) -> Self {
+        Self {
+            index: self.index.saturating_add(rhs.index),
+        }
+    }
+
+    /// Return `None` if the index exceeds `u32::MAX`, although the length is known.
+    /// Read `index` for the length in `u64`.
+    fn byte_len(&self) -> Option<u32> {
+        u32::try_from(self.index).ok()
+    }
+}
+
+impl AddAssign for Utf8Index64 {
+    fn add_assign(&mut self, rhs: Self) {
+        self.index = add_field64(self.index, rhs.index);
+    }
+}
+
+impl Add for Utf8Index64 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            index: add_field64(self.index, rhs.index),
+        }
+    }
+}
+
+impl From<u64> for Utf8Index64 {
+    fn from(index: u64) -> Self {
+        Self { index }
+    }
+}
+
+impl From<char> for Utf8Index64 {
+    fn from(c: char) -> Self {
+        Self {
+            index: c.len_utf8() as u64,
+        }
+    }
+}
+
+impl From<&'_ str> for Utf8Index64 {
+    fn from(s: &str) -> Self {
+        Self::from_str(s)
+    }
+}
+
+impl From<Utf8Index> for Utf8Index64 {
+    fn from(index: Utf8Index) -> Self {
+        Self {
+            index: index.index as u64,
+        }
+    }
+}
+
+impl TryFrom<Utf8Index64> for Utf8Index {
+    type Error = PositionError;
+
+    /// Fail if the index exceeds `u32::MAX`.
+    fn try_from(index: Utf8Index64) -> Result<Self, Self::Error> {
+        u32::try_from(index.index)
+            .map(Utf8Index::new)
+            .map_err(|_| PositionError::OutOfBounds)
+    }
+}
+
+impl Debug for Utf8Index64 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for Utf8Index64 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.index, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PositionError, TextPosition, Utf8Index, Utf8Index64};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_add_beyond_u32() {
+        let pos = Utf8Index64::new(u32::MAX as u64) + Utf8Index64::new(1);
+        assert_eq!(pos.index, 1 << 32);
+        assert_eq!(Utf8Index::try_from(pos), Err(PositionError::OutOfBounds));
+        assert_eq!(pos.byte_len(), None);
+    }
+
+    #[test]
+    fn test_try_from_in_range() {
+        let pos = Utf8Index64::from(Utf8Index::new(42));
+        assert_eq!(Utf8Index::try_from(pos), Ok(Utf8Index::new(42)));
+    }
+}
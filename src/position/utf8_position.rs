@@ -1,6 +1,9 @@
 // LICENSE: CC0-1.0
 
-use crate::TextPosition;
+use crate::{
+    position::{add_field, fmt_row_column, split_final_line, ZeroBased},
+    TextPosition, Utf8Index,
+};
 use std::{
     cmp::Ordering,
     fmt::{self, Debug, Display, Formatter},
@@ -11,6 +14,7 @@ use std::{
 /// Column number (= length of the final line) is measured as number of UTF-8 code units (bytes).
 /// Start from 0.
 #[derive(Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Utf8Position {
     pub row: u32,
     pub column: u32,
@@ -20,11 +24,104 @@ impl Utf8Position {
     pub const fn new(row: u32, column: u32) -> Self {
         Self { row, column }
     }
+
+    /// Display as 0-based `row:column`, unlike `Display` which is 1-based.
+    ///
+    /// ```
+    /// use text_position_rs::Utf8Position;
+    ///
+    /// let pos = Utf8Position::new(3, 1);
+    /// assert_eq!(format!("{}", pos), "4:2");
+    /// assert_eq!(format!("{}", pos.display_zero_based()), "3:1");
+    /// ```
+    pub fn display_zero_based(self) -> impl Display {
+        ZeroBased {
+            row: self.row,
+            column: self.column,
+        }
+    }
+
+    /// Advance by a character. Same as `self + Utf8Position::from(c)`.
+    pub fn advance(self, c: char) -> Self {
+        if c == '\n' {
+            Self {
+                row: add_field(self.row, 1),
+                column: 0,
+            }
+        } else {
+            Self {
+                row: self.row,
+                column: add_field(self.column, c.len_utf8() as u32),
+            }
+        }
+    }
+
+    /// Whether two positions are on the same line.
+    pub fn same_row(self, other: Self) -> bool {
+        self.row == other.row
+    }
+
+    /// Compare columns of two positions on the same line.
+    ///
+    /// Return `None` if they are on different lines,
+    /// since columns of different lines aren't comparable.
+    pub fn column_cmp(self, other: Self) -> Option<Ordering> {
+        if self.same_row(other) {
+            Some(self.column.cmp(&other.column))
+        } else {
+            None
+        }
+    }
+
+    /// Move left by columns, keeping the row. Column is clamped to 0.
+    pub fn saturating_sub_column(self, columns: u32) -> Self {
+        Self::new(self.row, self.column.saturating_sub(columns))
+    }
+
+    /// Move up by rows, keeping the column. Row is clamped to 0.
+    pub fn saturating_sub_row(self, rows: u32) -> Self {
+        Self::new(self.row.saturating_sub(rows), self.column)
+    }
+}
+
+impl Utf8Position {
+    /// Find the UTF-8 index of the position in the text.
+    ///
+    /// Return `None` if the row doesn't exist, the column exceeds the line, or it's in the middle of a character.
+    ///
+    /// ```
+    /// use text_position_rs::{Utf8Index, Utf8Position};
+    ///
+    /// let text = "ab\n🐧";
+    /// assert_eq!(Utf8Position::new(1, 4).to_byte_offset(text), Some(Utf8Index::new(7)));
+    /// assert_eq!(Utf8Position::new(1, 2).to_byte_offset(text), None);
+    /// ```
+    pub fn to_byte_offset(self, text: &str) -> Option<Utf8Index> {
+        let mut line_start = 0;
+        for _ in 0..self.row {
+            line_start += text[line_start..].find('\n')? + 1;
+        }
+
+        let line_end = text[line_start..]
+            .find('\n')
+            .map_or(text.len(), |i| line_start + i);
+        let offset = line_start + self.column as usize;
+        if offset <= line_end && text.is_char_boundary(offset) {
+            Some(Utf8Index::new(offset as u32))
+        } else {
+            None
+        }
+    }
 }
 
 impl TextPosition for Utf8Position {
     const ZERO: Self = Self { row: 0, column: 0 };
 
+    const MAX: Self = Self {
+        row: u32::MAX,
+        column: u32::MAX,
+    };
+
     fn from_str(s: &str) -> Self {
         let mut row = 0;
         let mut head = 0;
@@ -40,6 +137,14 @@ impl TextPosition for Utf8Position {
         }
     }
 
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let (row, final_line) = split_final_line(bytes);
+        Self {
+            row,
+            column: final_line.len() as u32,
+        }
+    }
+
     fn saturating_sub(self, rhs: Self) -> Self {
         match self.row.cmp(&rhs.row) {
             Ordering::Less => Self::ZERO,
@@ -53,6 +158,34 @@ impl TextPosition for Utf8Position {
             },
         }
     }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        if rhs.row == 0 {
+            Some(Self {
+                row: self.row,
+                column: self.column.checked_add(rhs.column)?,
+            })
+        } else {
+            Some(Self {
+                row: self.row.checked_add(rhs.row)?,
+                column: rhs.column,
+            })
+        }
+    }
+
+    fn saturating_add(self, rhs: Self) -> Self {
+        if rhs.row == 0 {
+            Self {
+                row: self.row,
+                column: self.column.saturating_add(rhs.column),
+            }
+        } else {
+            Self {
+                row: self.row.saturating_add(rhs.row),
+                column: rhs.column,
+            }
+        }
+    }
 }
 
 impl Add for Utf8Position {
@@ -62,11 +195,11 @@ impl Add for Utf8Position {
         if rhs.row == 0 {
             Self {
                 row: self.row,
-                column: self.column + rhs.column,
+                column: add_field(self.column, rhs.column),
             }
         } else {
             Self {
-                row: self.row + rhs.row,
+                row: add_field(self.row, rhs.row),
                 column: rhs.column,
             }
         }
@@ -99,6 +232,15 @@ impl From<&'_ str> for Utf8Position {
     }
 }
 
+/// Position at the column on the first row, for single-line text.
+///
+/// Row is always 0. Use `new` for multiline text.
+impl From<u32> for Utf8Position {
+    fn from(column: u32) -> Self {
+        Self::new(0, column)
+    }
+}
+
 impl From<Utf8Position> for (u32, u32) {
     fn from(pos: Utf8Position) -> (u32, u32) {
         (pos.row, pos.column)
@@ -113,7 +255,7 @@ impl Debug for Utf8Position {
 
 impl Display for Utf8Position {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}:{}", self.row + 1, self.column + 1)
+        fmt_row_column(f, self.row, self.column)
     }
 }
 
@@ -229,13 +371,83 @@ mod tests {
         assert_eq!(pos_at(4, 6).saturating_sub(pos_at(4, 1)), pos_at(0, 5));
     }
 
+    #[test]
+    fn test_checked_add_overflow() {
+        assert_eq!(pos_at(0, u32::MAX).checked_add(pos_at(0, 1)), None);
+        assert_eq!(pos_at(u32::MAX, 0).checked_add(pos_at(1, 1)), None);
+        assert_eq!(
+            pos_at(0, u32::MAX).checked_add(pos_at(1, 1)),
+            Some(pos_at(1, 1))
+        );
+    }
+
+    #[test]
+    fn test_saturating_add_overflow() {
+        assert_eq!(
+            pos_at(0, u32::MAX).saturating_add(pos_at(0, 1)),
+            pos_at(0, u32::MAX)
+        );
+        assert_eq!(
+            pos_at(u32::MAX, 3).saturating_add(pos_at(1, 1)),
+            pos_at(u32::MAX, 1)
+        );
+    }
+
+    #[test]
+    fn test_column_cmp() {
+        use std::cmp::Ordering;
+
+        assert!(pos_at(2, 3).same_row(pos_at(2, 8)));
+        assert_eq!(pos_at(2, 3).column_cmp(pos_at(2, 8)), Some(Ordering::Less));
+        assert_eq!(pos_at(2, 3).column_cmp(pos_at(2, 3)), Some(Ordering::Equal));
+        assert_eq!(pos_at(2, 3).column_cmp(pos_at(1, 3)), None);
+    }
+
     #[test]
     fn test_display_zero() {
         assert_eq!(format!("{}", ZERO), "1:1");
     }
 
+    #[test]
+    fn test_display_width() {
+        assert_eq!(format!("{:>8}", pos_at(0, 0)), "     1:1");
+        assert_eq!(format!("{:<8}|", pos_at(9, 9)), "10:10   |");
+    }
+
     #[test]
     fn test_display_nonzero() {
         assert_eq!(format!("{}", pos_at(3, 1)), "4:2");
     }
+
+    #[test]
+    fn test_from_column() {
+        assert_eq!(Utf8Position::from(7), Utf8Position::new(0, 7));
+    }
+
+    #[test]
+    fn test_to_byte_offset() {
+        let text = "12345\n1234567\n12345";
+        let offset = |row, column| {
+            Utf8Position::new(row, column)
+                .to_byte_offset(text)
+                .map(|index| index.index)
+        };
+        assert_eq!(offset(0, 0), Some(0));
+        assert_eq!(offset(1, 7), Some(13));
+        assert_eq!(offset(2, 5), Some(19));
+
+        // Past the end of line.
+        assert_eq!(offset(0, 6), None);
+        // Row doesn't exist.
+        assert_eq!(offset(3, 0), None);
+    }
+
+    #[test]
+    fn test_saturating_sub_axis() {
+        let pos = pos_at(2, 5);
+        assert_eq!(pos.saturating_sub_column(3), pos_at(2, 2));
+        assert_eq!(pos.saturating_sub_column(9), pos_at(2, 0));
+        assert_eq!(pos.saturating_sub_row(1), pos_at(1, 5));
+        assert_eq!(pos.saturating_sub_row(9), pos_at(0, 5));
+    }
 }
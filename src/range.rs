@@ -1,9 +1,15 @@
 // LICENSE: CC0-1.0
 
-use crate::{position::TextPosition, CompositePosition, Utf16Position, Utf8Index, Utf8Position};
+use crate::{
+    position::{add_field, TextPosition},
+    ByteCharPosition, CompositePosition, Delta, PositionError, Utf16Position, Utf8Index,
+    Utf8Position,
+};
 use std::{
+    cmp::Ordering,
+    convert::TryFrom,
     fmt::{self, Debug, Display, Formatter},
-    ops::{Add, Range},
+    ops::{Add, Range, RangeInclusive},
 };
 
 // DESIGN: Prefer (index, len) over (start, end)
@@ -25,7 +31,12 @@ use std::{
 /// let middle = Utf8Position::new(3, 3);
 /// assert!(range.contains_inclusive(middle));
 /// ```
+///
+/// `Ord` compares starts, then lengths (same as `cmp_by_start_then_len`).
+/// For ranges with the same start, comparing lengths is the same as comparing ends.
+/// See also `cmp_by_span`.
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextRange<P> {
     /// Start position of text range.
     pub index: P,
@@ -41,37 +52,168 @@ impl<P: TextPosition> TextRange<P> {
     };
 
     /// Create a range.
-    pub fn at(index: P, len: P) -> Self {
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// const HEADER_SPAN: TextRange<Utf8Index> = TextRange::at(Utf8Index::new(0), Utf8Index::new(4));
+    /// assert_eq!(HEADER_SPAN.end(), Utf8Index::new(4));
+    /// ```
+    pub const fn at(index: P, len: P) -> Self {
         Self { index, len }
     }
 
     /// Create an empty range pointing to a position.
-    pub fn empty(index: P) -> Self {
+    pub const fn empty(index: P) -> Self {
         Self {
             index,
             len: P::ZERO,
         }
     }
 
+    /// Same as `empty`. Reads better for cursors.
+    pub const fn collapsed_at(pos: P) -> Self {
+        Self::empty(pos)
+    }
+
     /// Create a range from origin to end.
-    pub fn up_to(len: P) -> Self {
+    pub const fn up_to(len: P) -> Self {
         Self {
             index: P::ZERO,
             len,
         }
     }
 
+    /// Create a range between two positions in any order.
+    ///
+    /// Unlike `From<Range<P>>`, reversed positions don't make an empty range.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let (anchor, cursor) = (Utf8Index::new(6), Utf8Index::new(2));
+    /// assert_eq!(
+    ///     TextRange::from_unordered(anchor, cursor),
+    ///     TextRange::from(cursor..anchor)
+    /// );
+    /// ```
+    pub fn from_unordered(a: P, b: P) -> Self {
+        if a <= b {
+            Self::from(a..b)
+        } else {
+            Self::from(b..a)
+        }
+    }
+
+    /// Create a range from a `(start, end)` pair. Same as `From<Range<P>>`.
+    pub fn from_tuple((start, end): (P, P)) -> Self {
+        Self::from(start..end)
+    }
+
+    /// Get the `(start, end)` pair.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(5));
+    /// match range.to_tuple() {
+    ///     (start, end) => assert_eq!((start.index, end.index), (2, 5)),
+    /// }
+    /// ```
+    pub fn to_tuple(self) -> (P, P) {
+        (self.clone().start(), self.end())
+    }
+
+    /// Length as a delta, distinguished from absolute positions.
+    pub fn delta(self) -> Delta<P> {
+        Delta(self.len)
+    }
+
+    /// Replace the start position, keeping the length.
+    pub fn with_index(self, index: P) -> Self {
+        Self { index, ..self }
+    }
+
+    /// Replace the length, keeping the start position.
+    pub fn with_len(self, len: P) -> Self {
+        Self { len, ..self }
+    }
+
+    /// Calculate the range of each fragment in the concatenation of them.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let ranges = TextRange::<Utf8Index>::concat_positions(vec!["ab", "", "cde"]);
+    /// assert_eq!(ranges[2], TextRange::from(Utf8Index::new(2)..Utf8Index::new(5)));
+    /// ```
+    pub fn concat_positions<'a>(fragments: impl IntoIterator<Item = &'a str>) -> Vec<Self> {
+        let mut running = P::ZERO;
+        fragments
+            .into_iter()
+            .map(|fragment| {
+                let range = Self::at(running.clone(), P::from_str(fragment));
+                running = range.clone().end();
+                range
+            })
+            .collect()
+    }
+
+    /// Cap the length, keeping the start position.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Position};
+    ///
+    /// let range = TextRange::at(Utf8Position::new(1, 2), Utf8Position::new(300, 4));
+    /// assert_eq!(
+    ///     range.clamp_len(Utf8Position::new(10, 0)),
+    ///     TextRange::at(Utf8Position::new(1, 2), Utf8Position::new(10, 0))
+    /// );
+    /// ```
+    pub fn clamp_len(self, max: P) -> Self {
+        Self {
+            index: self.index,
+            len: self.len.min(max),
+        }
+    }
+
+    /// Create the smallest range covering all of the positions.
+    ///
+    /// Return `None` if no position is given.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let positions = vec![Utf8Index::new(6), Utf8Index::new(2), Utf8Index::new(4)];
+    /// assert_eq!(
+    ///     TextRange::cover_positions(positions),
+    ///     Some(TextRange::from(Utf8Index::new(2)..Utf8Index::new(6)))
+    /// );
+    /// ```
+    pub fn cover_positions(positions: impl IntoIterator<Item = P>) -> Option<Self> {
+        let mut iter = positions.into_iter();
+        let first = iter.next()?;
+        let (start, end) = iter.fold((first.clone(), first), |(start, end), pos| {
+            (start.min(pos.clone()), end.max(pos))
+        });
+        Some(Self::from(start..end))
+    }
+
+    /// Start position, which is contained by the range unless empty.
     pub fn start(self) -> P {
         self.index
     }
 
+    /// End position, exclusive. That is, the position just after the range.
+    ///
+    /// `start() == end()` if the range is empty.
     pub fn end(self) -> P {
         self.index + self.len
     }
 
     /// Empty range pointing to the start position.
     pub fn to_start(self) -> Self {
-        Self::empty(self.end())
+        Self::empty(self.start())
     }
 
     /// Empty range pointing to the end position.
@@ -79,6 +221,88 @@ impl<P: TextPosition> TextRange<P> {
         Self::empty(self.end())
     }
 
+    /// Move the range forward, e.g. when text is inserted before it.
+    pub fn translate(self, delta: P) -> Self {
+        Self {
+            index: self.index + delta,
+            len: self.len,
+        }
+    }
+
+    /// Move the range backward, e.g. when text is deleted before it.
+    ///
+    /// The start position saturates to `ZERO`. The length is kept.
+    /// Use `remove_before` instead if the deleted text can overlap with the range.
+    pub fn translate_back(self, delta: P) -> Self {
+        Self {
+            index: self.index.saturating_sub(delta),
+            len: self.len,
+        }
+    }
+
+    /// Update the range for deletion of text in `deleted`.
+    ///
+    /// If the deletion is before the range, the range moves backward.
+    /// If the deletion overlaps with the range, the overlapped part is removed from the range.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = |start: u32, end: u32| TextRange::from(Utf8Index::new(start)..Utf8Index::new(end));
+    ///
+    /// // Before.
+    /// assert_eq!(range(5, 8).remove_before(range(1, 3)), range(3, 6));
+    /// // Overlapping with the start.
+    /// assert_eq!(range(5, 8).remove_before(range(3, 6)), range(3, 5));
+    /// ```
+    pub fn remove_before(self, deleted: Self) -> Self {
+        let deleted_start = deleted.clone().start();
+        let deleted_end = deleted.end();
+        let map = |pos: P| {
+            if pos <= deleted_start {
+                pos
+            } else if pos < deleted_end {
+                deleted_start.clone()
+            } else {
+                deleted_start.clone() + pos.saturating_sub(deleted_end.clone())
+            }
+        };
+
+        Self::from(map(self.clone().start())..map(self.end()))
+    }
+
+    /// Express the range relative to `origin`, e.g. for a slice of the text starting at `origin`.
+    ///
+    /// The start position saturates to `ZERO` if it's before `origin`. The length is kept.
+    pub fn relative_to(self, origin: P) -> Self {
+        Self {
+            index: self.index.saturating_sub(origin),
+            len: self.len,
+        }
+    }
+
+    /// Convert the range to another position type by converting both ends.
+    ///
+    /// The length is recalculated from the converted ends,
+    /// since it can't be converted independently of the start in general.
+    ///
+    /// ```
+    /// use text_position_rs::{LineIndex, TextRange, Utf8Index, Utf8Position};
+    ///
+    /// let text = "ab\ncd\nef";
+    /// let line_index = LineIndex::new(text);
+    /// let range = TextRange::from(Utf8Index::new(1)..Utf8Index::new(6));
+    /// assert_eq!(
+    ///     range.convert(|index| line_index.position(index)),
+    ///     TextRange::from(Utf8Position::new(0, 1)..Utf8Position::new(2, 0))
+    /// );
+    /// ```
+    pub fn convert<Q: TextPosition>(self, f: impl Fn(P) -> Q) -> TextRange<Q> {
+        let start = f(self.clone().start());
+        let end = f(self.end());
+        TextRange::from(start..end)
+    }
+
     /// Whether the range contains a position inclusively.
     ///
     /// True if `pos == self.end()`.
@@ -89,12 +313,71 @@ impl<P: TextPosition> TextRange<P> {
         pos >= self.index && pos <= self.end()
     }
 
+    /// Compare a position to the range, regarding the range as the half-open interval `[start, end)`.
+    ///
+    /// Return `Less` if the position is before the range,
+    /// `Equal` if it's in the range and `Greater` if it's at or after the end.
+    /// Note no position is in an empty range.
+    ///
+    /// The result is the ordering of the position relative to the range.
+    /// Use `.reverse()` for `slice::binary_search_by`, which expects the opposite.
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(4));
+    /// assert_eq!(range.locate(Utf8Index::new(1)), Ordering::Less);
+    /// assert_eq!(range.locate(Utf8Index::new(2)), Ordering::Equal);
+    /// assert_eq!(range.locate(Utf8Index::new(4)), Ordering::Greater);
+    /// ```
+    pub fn locate(self, pos: P) -> Ordering {
+        if pos < self.index {
+            Ordering::Less
+        } else if pos < self.end() {
+            Ordering::Equal
+        } else {
+            Ordering::Greater
+        }
+    }
+
     /// Whether the range contains another range entirely.
     pub fn covers(self, other: Self) -> bool {
         // QUESTION: More efficient way?
         self.clone().start() <= other.clone().start() && other.end() <= self.end()
     }
 
+    /// Same as `covers` but takes a range of the standard library.
+    ///
+    /// `other` is regarded as empty if its end is before its start.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(6));
+    /// assert!(range.covers_range(Utf8Index::new(3)..Utf8Index::new(6)));
+    /// assert!(!range.covers_range(Utf8Index::new(1)..Utf8Index::new(3)));
+    /// ```
+    pub fn covers_range(self, other: Range<P>) -> bool {
+        self.covers(Self::from(other))
+    }
+
+    /// Whether the range contains another range within the half-open interval `[start, end)`.
+    ///
+    /// Unlike `covers`, `other` must end before `self.end()`.
+    /// Thus an empty range strictly covers nothing.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(6));
+    /// assert!(range.strictly_covers(TextRange::from(Utf8Index::new(2)..Utf8Index::new(4))));
+    /// assert!(!range.strictly_covers(TextRange::from(Utf8Index::new(4)..Utf8Index::new(6))));
+    /// ```
+    pub fn strictly_covers(self, other: Self) -> bool {
+        self.clone().start() <= other.clone().start() && other.end() < self.end()
+    }
+
     /// Whether the range is empty.
     ///
     /// ```
@@ -112,6 +395,20 @@ impl<P: TextPosition> TextRange<P> {
         self.len == P::ZERO
     }
 
+    /// Whether the range is `ZERO`, i.e. empty and pointing to the origin.
+    ///
+    /// Useful to tell an unset range apart from an empty range pointing to somewhere else.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Position};
+    ///
+    /// assert!(TextRange::<Utf8Position>::ZERO.is_degenerate());
+    /// assert!(!TextRange::empty(Utf8Position::new(5, 0)).is_degenerate());
+    /// ```
+    pub fn is_degenerate(self) -> bool {
+        self == Self::ZERO
+    }
+
     /// Make a range covering two ranges.
     ///
     /// c.f. <https://en.wikipedia.org/wiki/Join_and_meet>.
@@ -133,6 +430,199 @@ impl<P: TextPosition> TextRange<P> {
         let end = self.end().max(other.end());
         Self::from(start..end)
     }
+
+    /// Compare starts, then lengths. Same as `Ord`.
+    pub fn cmp_by_start_then_len(&self, other: &Self) -> Ordering {
+        self.index
+            .cmp(&other.index)
+            .then_with(|| self.len.cmp(&other.len))
+    }
+
+    /// Compare starts, then lengths in descending order,
+    /// so that a range comes before ranges nested in it, e.g. to layer tokens.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = |start, end| TextRange::from(Utf8Index::new(start)..Utf8Index::new(end));
+    /// let mut ranges = vec![range(2, 4), range(2, 8), range(0, 3)];
+    /// ranges.sort_by(TextRange::cmp_by_span);
+    /// assert_eq!(ranges, vec![range(0, 3), range(2, 8), range(2, 4)]);
+    /// ```
+    pub fn cmp_by_span(&self, other: &Self) -> Ordering {
+        self.index
+            .cmp(&other.index)
+            .then_with(|| other.len.cmp(&self.len))
+    }
+
+    /// Whether two ranges start at the same position, ignoring lengths.
+    ///
+    /// Unlike `==`, an empty range and a non-empty range can be at the same position, e.g. to compare cursors.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let cursor = TextRange::empty(Utf8Index::new(2));
+    /// let selection = TextRange::from(Utf8Index::new(2)..Utf8Index::new(5));
+    /// assert!(cursor.same_position(selection));
+    /// assert_ne!(cursor, selection);
+    /// ```
+    pub fn same_position(self, other: Self) -> bool {
+        self.start() == other.start()
+    }
+
+    /// Earliest position where two ranges differ, e.g. to redraw only from there.
+    ///
+    /// That's the smaller start if starts differ, or the smaller end if only ends differ.
+    /// Return `None` if the ranges are equal.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(6));
+    /// let other = TextRange::from(Utf8Index::new(2)..Utf8Index::new(4));
+    /// assert_eq!(range.first_difference(other), Some(Utf8Index::new(4)));
+    /// assert_eq!(range.first_difference(range), None);
+    /// ```
+    pub fn first_difference(self, other: Self) -> Option<P> {
+        let (start, other_start) = (self.clone().start(), other.clone().start());
+        if start != other_start {
+            return Some(start.min(other_start));
+        }
+
+        let (end, other_end) = (self.end(), other.end());
+        if end != other_end {
+            return Some(end.min(other_end));
+        }
+
+        None
+    }
+
+    /// Move the end forward to `new_end`, keeping the start. No-op if `new_end` is before the end.
+    ///
+    /// Unlike `join`, this grows the range only in one direction, e.g. to accumulate a span while parsing.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(4));
+    /// assert_eq!(
+    ///     range.extend_end(Utf8Index::new(7)),
+    ///     TextRange::from(Utf8Index::new(2)..Utf8Index::new(7))
+    /// );
+    /// ```
+    pub fn extend_end(self, new_end: P) -> Self {
+        let start = self.clone().start();
+        let end = self.end().max(new_end);
+        Self::from(start..end)
+    }
+
+    /// Move the start backward to `new_start`, keeping the end. No-op if `new_start` is after the start.
+    pub fn extend_start(self, new_start: P) -> Self {
+        let start = self.clone().start().min(new_start);
+        let end = self.end();
+        Self::from(start..end)
+    }
+
+    /// Merge neighboring items whose ranges touch and whose payloads are equal by `eq`, e.g. spans of the same style.
+    ///
+    /// Items should be sorted. Only consecutive items are merged, where the end of one is the start of the next.
+    /// The payload of the first item is kept.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = |start, end| TextRange::from(Utf8Index::new(start)..Utf8Index::new(end));
+    /// let merged = TextRange::merge_adjacent_by(
+    ///     vec![(range(0, 2), "kw"), (range(2, 5), "kw"), (range(5, 6), "op")],
+    ///     |a, b| a == b,
+    /// );
+    /// assert_eq!(merged, vec![(range(0, 5), "kw"), (range(5, 6), "op")]);
+    /// ```
+    pub fn merge_adjacent_by<T>(
+        items: Vec<(Self, T)>,
+        eq: impl Fn(&T, &T) -> bool,
+    ) -> Vec<(Self, T)> {
+        let mut merged: Vec<(Self, T)> = Vec::with_capacity(items.len());
+        for (range, payload) in items {
+            if let Some((last, last_payload)) = merged.last_mut() {
+                if last.clone().end() == range.clone().start() && eq(last_payload, &payload) {
+                    *last = last.clone().join(range);
+                    continue;
+                }
+            }
+            merged.push((range, payload));
+        }
+        merged
+    }
+
+    /// Make a range covering two ranges if they overlap or touch.
+    ///
+    /// Unlike `join`, return `None` if there is a gap between them.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = |start: u32, end: u32| TextRange::from(Utf8Index::new(start)..Utf8Index::new(end));
+    /// assert_eq!(range(2, 4).union(range(3, 6)), Some(range(2, 6)));
+    /// assert_eq!(range(2, 4).union(range(4, 6)), Some(range(2, 6)));
+    /// assert_eq!(range(2, 4).union(range(5, 6)), None);
+    /// ```
+    pub fn union(self, other: Self) -> Option<Self> {
+        if self.clone().start() <= other.clone().end()
+            && other.clone().start() <= self.clone().end()
+        {
+            Some(self.join(other))
+        } else {
+            None
+        }
+    }
+
+    /// Make the common part of two ranges.
+    ///
+    /// Return `None` if there is a gap between them.
+    /// Return an empty range if they just touch.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = |start: u32, end: u32| TextRange::from(Utf8Index::new(start)..Utf8Index::new(end));
+    /// assert_eq!(range(2, 5).intersect(range(3, 8)), Some(range(3, 5)));
+    /// assert_eq!(range(2, 5).intersect(range(5, 8)), Some(range(5, 5)));
+    /// assert_eq!(range(2, 5).intersect(range(6, 8)), None);
+    /// ```
+    pub fn intersect(self, other: Self) -> Option<Self> {
+        let start = self.clone().start().max(other.clone().start());
+        let end = self.end().min(other.end());
+        if start <= end {
+            Some(Self::from(start..end))
+        } else {
+            None
+        }
+    }
+
+    /// Length of the common part of two ranges, or `ZERO` if they are disjoint.
+    ///
+    /// For row-column pairs, the length is a delta as `len` is.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = |start, end| TextRange::from(Utf8Index::new(start)..Utf8Index::new(end));
+    /// assert_eq!(range(2, 6).overlap_len(range(4, 9)), Utf8Index::new(2));
+    /// ```
+    pub fn overlap_len(self, other: Self) -> P {
+        self.intersect(other).map_or(P::ZERO, |range| range.len)
+    }
+
+    /// Make the common part of all ranges.
+    ///
+    /// Return `None` if no range is given or some of them don't intersect.
+    pub fn intersect_all(ranges: impl IntoIterator<Item = Self>) -> Option<Self> {
+        let mut iter = ranges.into_iter();
+        let first = iter.next()?;
+        iter.try_fold(first, Self::intersect)
+    }
 }
 
 impl<P: TextPosition + Default> Default for TextRange<P> {
@@ -151,6 +641,15 @@ impl<P: TextPosition> From<Range<P>> for TextRange<P> {
     }
 }
 
+/// Range of a character, starting at `ZERO`.
+///
+/// Use `translate` to move it to the actual position.
+impl<P: TextPosition + From<char>> From<char> for TextRange<P> {
+    fn from(c: char) -> Self {
+        Self::up_to(P::from(c))
+    }
+}
+
 impl<P: TextPosition + Add<Output = P>> From<TextRange<P>> for Range<P> {
     fn from(range: TextRange<P>) -> Self {
         let TextRange { index, len } = range;
@@ -161,61 +660,608 @@ impl<P: TextPosition + Add<Output = P>> From<TextRange<P>> for Range<P> {
     }
 }
 
-impl Debug for TextRange<Utf8Index> {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        Display::fmt(self, f)
+impl From<TextRange<CompositePosition>> for TextRange<Utf8Index> {
+    fn from(range: TextRange<CompositePosition>) -> Self {
+        Self {
+            index: range.index.into(),
+            len: range.len.into(),
+        }
     }
 }
 
-impl Display for TextRange<Utf8Index> {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{}..{}", self.start(), self.end())
+impl From<TextRange<CompositePosition>> for TextRange<Utf8Position> {
+    fn from(range: TextRange<CompositePosition>) -> Self {
+        Self {
+            index: range.index.into(),
+            len: range.len.into(),
+        }
     }
 }
 
-impl Debug for TextRange<Utf8Position> {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        Display::fmt(self, f)
+impl From<TextRange<CompositePosition>> for TextRange<Utf16Position> {
+    fn from(range: TextRange<CompositePosition>) -> Self {
+        Self {
+            index: range.index.into(),
+            len: range.len.into(),
+        }
     }
 }
 
-/// <https://www.gnu.org/prep/standards/html_node/Errors.html>
-fn fmt_gnu(
-    f: &mut Formatter,
-    start_row: u32,
-    start_column: u32,
-    end_row: u32,
-    end_column: u32,
-) -> fmt::Result {
-    write!(
-        f,
-        "{}.{}-{}.{}",
-        start_row + 1,
-        start_column + 1,
-        end_row + 1,
-        end_column + 1
-    )
-}
-
-impl Display for TextRange<Utf8Position> {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        let start = self.start();
-        let end = self.end();
-        fmt_gnu(f, start.row, start.column, end.row, end.column)
+impl TextRange<Utf8Index> {
+    /// Convert to a range of `usize` to slice a string.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let text = "Hello, world!";
+    /// let range = TextRange::from(Utf8Index::new(7)..Utf8Index::new(12));
+    /// assert_eq!(&text[range.byte_range()], "world");
+    /// ```
+    pub fn byte_range(self) -> Range<usize> {
+        self.start().into()..self.end().into()
     }
-}
 
-impl Debug for TextRange<Utf16Position> {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        Display::fmt(self, f)
-    }
+    /// Convert ranges of `usize`, e.g. from other libraries.
+    ///
+    /// Each range is empty if its end is before its start, same as `TryFrom<Range<usize>>`.
+    /// Fail if any offset exceeds `u32::MAX`.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let ranges = TextRange::from_byte_ranges(vec![0..2, 5..8, 9..7]).unwrap();
+    /// assert_eq!(ranges[1], TextRange::from(Utf8Index::new(5)..Utf8Index::new(8)));
+    /// assert_eq!(ranges[2], TextRange::empty(Utf8Index::new(9)));
+    /// ```
+    pub fn from_byte_ranges(
+        ranges: impl IntoIterator<Item = Range<usize>>,
+    ) -> Result<Vec<Self>, PositionError> {
+        ranges.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Ranges of lines in the text, excluding newlines.
+    ///
+    /// Same as `str::lines`, no empty line follows the final newline.
+    /// Unlike that, `\r` before newline isn't excluded.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let text = "ab\n\ncd\n";
+    /// let lines = TextRange::line_ranges(text).map(|r| &text[r.byte_range()]).collect::<Vec<_>>();
+    /// assert_eq!(lines, vec!["ab", "", "cd"]);
+    /// ```
+    pub fn line_ranges(text: &str) -> impl Iterator<Item = TextRange<Utf8Index>> + '_ {
+        let mut head = 0;
+
+        std::iter::from_fn(move || {
+            if head >= text.len() {
+                return None;
+            }
+
+            let line_start = head;
+            let line_end = text[line_start..]
+                .find('\n')
+                .map_or(text.len(), |i| line_start + i);
+            head = line_end + 1;
+            Some(TextRange::from(
+                Utf8Index::new(line_start as u32)..Utf8Index::new(line_end as u32),
+            ))
+        })
+    }
+
+    /// Same as `line_ranges` but from the last line to the first.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let text = "ab\n\ncd\n";
+    /// let lines = TextRange::line_ranges_rev(text).map(|r| &text[r.byte_range()]).collect::<Vec<_>>();
+    /// assert_eq!(lines, vec!["cd", "", "ab"]);
+    /// ```
+    pub fn line_ranges_rev(text: &str) -> impl Iterator<Item = TextRange<Utf8Index>> + '_ {
+        let mut tail = if text.is_empty() {
+            None
+        } else {
+            Some(text.strip_suffix('\n').unwrap_or(text).len())
+        };
+
+        std::iter::from_fn(move || {
+            let line_end = tail?;
+            let line_start = text[..line_end].rfind('\n').map_or(0, |i| i + 1);
+            tail = line_start.checked_sub(1);
+            Some(TextRange::from(
+                Utf8Index::new(line_start as u32)..Utf8Index::new(line_end as u32),
+            ))
+        })
+    }
+
+    /// Split the range into lines of the text.
+    ///
+    /// Each item is the intersection of the range and a line, excluding the newline.
+    /// The first and last items can be part of lines; others are whole lines.
+    /// Same as `str::lines`, a newline at the end of the range doesn't make an empty item after it.
+    ///
+    /// The range is clamped to the text and snapped outward to character boundaries.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let text = "fn f() {\n    g();\n}\n";
+    /// let range = TextRange::from(Utf8Index::new(3)..Utf8Index::new(19));
+    /// let lines = range.lines(text).map(|r| &text[r.byte_range()]).collect::<Vec<_>>();
+    /// assert_eq!(lines, vec!["f() {", "    g();", "}"]);
+    /// ```
+    pub fn lines<'a>(self, text: &'a str) -> impl Iterator<Item = TextRange<Utf8Index>> + 'a {
+        let start = self.start().snap_to_char_boundary(text).index as usize;
+        let end = self.end().snap_to_char_boundary_up(text).index as usize;
+        let mut head = Some(text[..start].rfind('\n').map_or(0, |i| i + 1));
+
+        std::iter::from_fn(move || {
+            let line_start = head?;
+            let line_end = text[line_start..]
+                .find('\n')
+                .map_or(text.len(), |i| line_start + i);
+
+            head = if line_end + 1 < end {
+                Some(line_end + 1)
+            } else {
+                None
+            };
+
+            Some(TextRange::from(
+                Utf8Index::new(line_start.max(start) as u32)
+                    ..Utf8Index::new(line_end.min(end) as u32),
+            ))
+        })
+    }
+
+    /// Split the range into maximal sub-ranges of characters satisfying the predicate.
+    ///
+    /// Characters not satisfying the predicate are skipped.
+    /// Panic if the range is out of the text or not on character boundaries, same as slicing.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let text = "let x1 = y;";
+    /// let range = TextRange::from(Utf8Index::new(0)..Utf8Index::new(11));
+    /// let words = range
+    ///     .split_by(text, char::is_alphanumeric)
+    ///     .map(|r| &text[r.byte_range()])
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(words, vec!["let", "x1", "y"]);
+    /// ```
+    pub fn split_by<'a>(
+        self,
+        text: &'a str,
+        pred: impl Fn(char) -> bool + 'a,
+    ) -> impl Iterator<Item = TextRange<Utf8Index>> + 'a {
+        let offset = self.start().index;
+        let mut chars = text[self.byte_range()].char_indices().peekable();
+
+        std::iter::from_fn(move || {
+            let (start, c) = chars.find(|&(_, c)| pred(c))?;
+            let mut end = start + c.len_utf8();
+            while let Some((i, c)) = chars.next_if(|&(_, c)| pred(c)) {
+                end = i + c.len_utf8();
+            }
+
+            Some(TextRange::from(
+                Utf8Index::new(offset + start as u32)..Utf8Index::new(offset + end as u32),
+            ))
+        })
+    }
+
+    /// Convert to a range of row-column pairs in the text.
+    ///
+    /// Return `None` if the range is out of the text or not on character boundaries.
+    /// This scans the text before the end. Use `LineIndex` to convert many ranges.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index, Utf8Position};
+    ///
+    /// let text = "let x = 1;\nlet y = x;";
+    /// let range = TextRange::from(Utf8Index::new(15)..Utf8Index::new(16));
+    /// assert_eq!(
+    ///     range.to_utf8_position_range(text),
+    ///     Some(TextRange::from(Utf8Position::new(1, 4)..Utf8Position::new(1, 5)))
+    /// );
+    /// ```
+    pub fn to_utf8_position_range(self, text: &str) -> Option<TextRange<Utf8Position>> {
+        let Range { start, end } = self.byte_range();
+        let (head, body) = (text.get(..start)?, text.get(start..end)?);
+        Some(TextRange::at(
+            Utf8Position::from_str(head),
+            Utf8Position::from_str(body),
+        ))
+    }
+
+    /// Number of UTF-16 code units of the text in the range.
+    ///
+    /// Panic if the range is out of the text or not on character boundaries, same as slicing.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let text = "a🐧b";
+    /// let range = TextRange::from(Utf8Index::new(0)..Utf8Index::new(5));
+    /// assert_eq!(range.utf16_units(text), 3);
+    /// ```
+    pub fn utf16_units(self, text: &str) -> u32 {
+        text[self.byte_range()].encode_utf16().count() as u32
+    }
+
+    /// Last index contained by the range, i.e. `end() - 1`.
+    ///
+    /// Return `None` if the range is empty.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(5));
+    /// assert_eq!(range.last_contained(), Some(Utf8Index::new(4)));
+    /// assert_eq!(TextRange::empty(Utf8Index::new(2)).last_contained(), None);
+    /// ```
+    pub fn last_contained(self) -> Option<Utf8Index> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(Utf8Index::new(self.end().index - 1))
+        }
+    }
+
+    /// Offset of a position from the start, e.g. to index into the substring of the range.
+    ///
+    /// Return `None` if the position is out of the range. The end is included.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let text = "let x = 42;";
+    /// let range = TextRange::from(Utf8Index::new(8)..Utf8Index::new(10));
+    /// let offset = range.offset_of(Utf8Index::new(9)).unwrap();
+    /// assert_eq!(&text[range.byte_range()][usize::from(offset)..], "2");
+    /// ```
+    pub fn offset_of(self, pos: Utf8Index) -> Option<Utf8Index> {
+        if self.contains_inclusive(pos) {
+            Some(pos.saturating_sub(self.start()))
+        } else {
+            None
+        }
+    }
+
+    /// Midpoint of the range, rounded down.
+    ///
+    /// Note the result can be in the middle of a character. Use `Utf8Index::snap_to_char_boundary` if needed.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(7));
+    /// assert_eq!(range.center(), Utf8Index::new(4));
+    /// ```
+    pub fn center(self) -> Utf8Index {
+        Utf8Index::new(self.index.index + self.len.index / 2)
+    }
+
+    /// Iterate over indices in the range, from `start()` to `end()` exclusive.
+    ///
+    /// This steps by bytes, so indices can be in the middle of characters.
+    /// Use `str::char_indices` to iterate over characters.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(5));
+    /// let indices = range.indices().map(|i| i.index).collect::<Vec<_>>();
+    /// assert_eq!(indices, vec![2, 3, 4]);
+    /// assert_eq!(range.indices().len(), 3);
+    /// ```
+    pub fn indices(self) -> impl ExactSizeIterator<Item = Utf8Index> + DoubleEndedIterator {
+        (self.start().index..self.end().index).map(Utf8Index::new)
+    }
+
+    /// Format as `start:len`.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(5));
+    /// assert_eq!(range.display_len().to_string(), "2:3");
+    /// ```
+    pub fn display_len(self) -> impl Display {
+        struct DisplayLen(TextRange<Utf8Index>);
+
+        impl Display for DisplayLen {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                write!(f, "{}:{}", self.0.start(), self.0.len)
+            }
+        }
+
+        DisplayLen(self)
+    }
+
+    /// Format as half-open interval `[start, end)`.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Index};
+    ///
+    /// let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(5));
+    /// assert_eq!(range.display_bracketed().to_string(), "[2, 5)");
+    /// ```
+    pub fn display_bracketed(self) -> impl Display {
+        struct DisplayBracketed(TextRange<Utf8Index>);
+
+        impl Display for DisplayBracketed {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                write!(f, "[{}, {})", self.0.start(), self.0.end())
+            }
+        }
+
+        DisplayBracketed(self)
+    }
 }
 
+impl TextRange<Utf8Position> {
+    /// Slice the text by the range.
+    ///
+    /// Return `None` if either end is out of the text or in the middle of a character.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Position};
+    ///
+    /// let text = "いろは\nにほへと";
+    /// let range = TextRange::from(Utf8Position::new(0, 3)..Utf8Position::new(1, 6));
+    /// assert_eq!(range.substr(text), Some("ろは\nにほ"));
+    /// ```
+    pub fn substr(self, text: &str) -> Option<&str> {
+        let start = self.start().to_byte_offset(text)?;
+        let end = self.end().to_byte_offset(text)?;
+        text.get(usize::from(start)..usize::from(end))
+    }
+
+    /// Render carets (`^`) under the range in the line of its start, e.g. for diagnostics.
+    ///
+    /// One space or caret is printed per character, regardless of its display width.
+    /// Carets don't line up with wide characters, e.g. in East Asian text, which take two cells in terminals.
+    /// Tabs before the range are kept so that the carets line up with the line in any tab width.
+    /// A multiline range is underlined to the end of the line. An empty range gets a single caret.
+    /// Columns out of the line or in the middle of characters are clamped.
+    ///
+    /// ```
+    /// use text_position_rs::{TextRange, Utf8Position};
+    ///
+    /// let line = "\tlet x = y;";
+    /// let range = TextRange::from(Utf8Position::new(0, 9)..Utf8Position::new(0, 10));
+    /// assert_eq!(range.underline(line), "\t        ^");
+    /// ```
+    pub fn underline(self, line: &str) -> String {
+        let floor = |column: u32| Utf8Index::new(column).snap_to_char_boundary(line).index as usize;
+
+        let start = floor(self.start().column);
+        let end = if self.is_multiline() {
+            line.len()
+        } else {
+            floor(self.end().column)
+        };
+
+        let mut underline = line[..start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let width = line[start..end].chars().count().max(1);
+        underline += &"^".repeat(width);
+        underline
+    }
+}
+
+// Methods on rows of ranges, for positions with `row` field.
+macro_rules! impl_rows {
+    ($($p:ty),*) => {$(
+        impl TextRange<$p> {
+            /// Number of lines the range spans. A range within a line spans 1 line.
+            pub fn line_count(self) -> u32 {
+                add_field(self.end().row - self.start().row, 1)
+            }
+
+            /// Whether the range spans multiple lines.
+            ///
+            /// A range ending at the start of the next line is multiline, since it contains a newline.
+            pub fn is_multiline(self) -> bool {
+                self.start().row != self.end().row
+            }
+        }
+    )*};
+}
+
+// Not for `CompositePosition`, whose index would depend on the length of the inserted lines.
+macro_rules! impl_shift_rows {
+    ($($p:ty),*) => {$(
+        impl TextRange<$p> {
+            /// Move the range down by lines, e.g. when lines are inserted before it. Columns are kept.
+            pub fn shift_rows(self, delta: u32) -> Self {
+                let mut index = self.index;
+                index.row = add_field(index.row, delta);
+                Self::at(index, self.len)
+            }
+
+            /// Move the range up by lines, e.g. when lines before it are deleted. Columns are kept.
+            ///
+            /// The row saturates to 0.
+            pub fn shift_rows_up(self, delta: u32) -> Self {
+                let mut index = self.index;
+                index.row = index.row.saturating_sub(delta);
+                Self::at(index, self.len)
+            }
+        }
+    )*};
+}
+
+impl_rows!(Utf8Position, Utf16Position, CompositePosition);
+impl_shift_rows!(Utf8Position, Utf16Position);
+
+/// Inverse of `byte_range`.
+/// The range is empty if the end is before the start.
+impl TryFrom<Range<usize>> for TextRange<Utf8Index> {
+    type Error = PositionError;
+
+    /// Fail if an offset exceeds `u32::MAX`, same as `TryFrom<usize>` for `Utf8Index`.
+    fn try_from(range: Range<usize>) -> Result<Self, Self::Error> {
+        let Range { start, end } = range;
+        Ok(Self::from(
+            Utf8Index::try_from(start)?..Utf8Index::try_from(end)?,
+        ))
+    }
+}
+
+/// Range including the end.
+/// Only for `Utf8Index`, where the next position of the end is unambiguous.
+/// The range is empty if the end is before the start, same as `RangeInclusive::is_empty`.
+/// The end saturates at `u32::MAX`, so a range ending at `u32::MAX` excludes it.
+///
+/// ```
+/// use text_position_rs::{TextRange, Utf8Index};
+///
+/// let range = TextRange::from(Utf8Index::new(2)..=Utf8Index::new(4));
+/// assert_eq!(range, TextRange::from(Utf8Index::new(2)..Utf8Index::new(5)));
+/// ```
+impl From<RangeInclusive<Utf8Index>> for TextRange<Utf8Index> {
+    fn from(range: RangeInclusive<Utf8Index>) -> Self {
+        let (start, end) = range.into_inner();
+        if end < start {
+            return Self::empty(start);
+        }
+        Self::from(start..Utf8Index::new(end.index.saturating_add(1)))
+    }
+}
+
+/// Same as `byte_range`. Useful for libraries taking `Range<usize>`, e.g. `codespan-reporting`.
+impl From<TextRange<Utf8Index>> for Range<usize> {
+    fn from(range: TextRange<Utf8Index>) -> Self {
+        range.byte_range()
+    }
+}
+
+#[cfg(feature = "miette")]
+impl From<TextRange<Utf8Index>> for miette::SourceSpan {
+    fn from(range: TextRange<Utf8Index>) -> Self {
+        (range.index.index as usize, range.len.index as usize).into()
+    }
+}
+
+#[cfg(feature = "text-size")]
+impl From<TextRange<Utf8Index>> for text_size::TextRange {
+    fn from(range: TextRange<Utf8Index>) -> Self {
+        text_size::TextRange::at(range.index.index.into(), range.len.index.into())
+    }
+}
+
+#[cfg(feature = "text-size")]
+impl From<text_size::TextRange> for TextRange<Utf8Index> {
+    fn from(range: text_size::TextRange) -> Self {
+        TextRange::at(
+            Utf8Index::new(range.start().into()),
+            Utf8Index::new(range.len().into()),
+        )
+    }
+}
+
+impl Debug for TextRange<Utf8Index> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for TextRange<Utf8Index> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}..{}", self.start(), self.end())
+    }
+}
+
+impl Debug for TextRange<ByteCharPosition> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for TextRange<ByteCharPosition> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}..{}", self.start(), self.end())
+    }
+}
+
+#[cfg(feature = "index64")]
+impl Debug for TextRange<crate::Utf8Index64> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(feature = "index64")]
+impl Display for TextRange<crate::Utf8Index64> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}..{}", self.start(), self.end())
+    }
+}
+
+impl Debug for TextRange<Utf8Position> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// <https://www.gnu.org/prep/standards/html_node/Errors.html>
+fn fmt_gnu(
+    f: &mut Formatter,
+    start_row: u32,
+    start_column: u32,
+    end_row: u32,
+    end_column: u32,
+) -> fmt::Result {
+    write!(
+        f,
+        "{}.{}-{}.{}",
+        start_row + 1,
+        start_column + 1,
+        end_row + 1,
+        end_column + 1
+    )
+}
+
+/// Format as `row.column-row.column` (1-based) in GNU style.
+/// The alternate form (`{:#}`) appends the encoding, ` [utf8]`.
+impl Display for TextRange<Utf8Position> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let start = self.start();
+        let end = self.end();
+        fmt_gnu(f, start.row, start.column, end.row, end.column)?;
+
+        if f.alternate() {
+            write!(f, " [utf8]")?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for TextRange<Utf16Position> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// Format as `row.column-row.column` (1-based) in GNU style.
+/// The alternate form (`{:#}`) appends the encoding, ` [utf16]`.
 impl Display for TextRange<Utf16Position> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let start = self.start();
         let end = self.end();
-        fmt_gnu(f, start.row, start.column, end.row, end.column)
+        fmt_gnu(f, start.row, start.column, end.row, end.column)?;
+
+        if f.alternate() {
+            write!(f, " [utf16]")?;
+        }
+        Ok(())
     }
 }
 
@@ -225,17 +1271,34 @@ impl Debug for TextRange<CompositePosition> {
     }
 }
 
+/// Format as `row.column-row.column` (1-based) in GNU style, measuring columns in UTF-8.
+/// The alternate form (`{:#}`) appends the range of UTF-8 indices.
+///
+/// ```
+/// use text_position_rs::{CompositePosition, TextRange};
+///
+/// let range = TextRange::from(CompositePosition::from("Hello, ")..CompositePosition::from("Hello, world"));
+/// assert_eq!(format!("{}", range), "1.8-1.13");
+/// assert_eq!(format!("{:#}", range), "1.8-1.13 (@7..12)");
+/// ```
 impl Display for TextRange<CompositePosition> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let start = self.start();
         let end = self.end();
-        fmt_gnu(f, start.row, start.column8, end.row, end.column8)
+        fmt_gnu(f, start.row, start.column8, end.row, end.column8)?;
+
+        if f.alternate() {
+            write!(f, " (@{}..{})", start.index, end.index)?;
+        }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{CompositePosition, TextPosition, TextRange, Utf8Position};
+    use crate::{
+        CompositePosition, TextPosition, TextRange, Utf16Position, Utf8Index, Utf8Position,
+    };
 
     #[test]
     fn test_contains_inclusive_for_other_type() {
@@ -247,6 +1310,672 @@ mod tests {
         assert!(range.contains_inclusive(Utf8Position::from(s)));
     }
 
+    #[test]
+    fn test_cover_positions_empty() {
+        assert_eq!(TextRange::<Utf8Position>::cover_positions(Vec::new()), None);
+    }
+
+    #[test]
+    fn test_cover_positions_shuffled() {
+        let positions = [
+            Utf8Position::new(3, 1),
+            Utf8Position::new(0, 7),
+            Utf8Position::new(5, 2),
+            Utf8Position::new(1, 0),
+        ];
+        let expected = TextRange::from(Utf8Position::new(0, 7)..Utf8Position::new(5, 2));
+
+        assert_eq!(
+            TextRange::cover_positions(positions.to_vec()),
+            Some(expected)
+        );
+
+        let mut reversed = positions.to_vec();
+        reversed.reverse();
+        assert_eq!(TextRange::cover_positions(reversed), Some(expected));
+    }
+
+    #[test]
+    fn test_relative_to_round_trip() {
+        fn pos_of(s: &str) -> Utf8Position {
+            Utf8Position::from_str(s)
+        }
+
+        let origin = pos_of("fn f() {\n    ");
+        let range = TextRange::from(pos_of("fn f() {\n    let ")..pos_of("fn f() {\n    let x"));
+        let relative = range.relative_to(origin);
+        assert_eq!(relative, TextRange::from(pos_of("let ")..pos_of("let x")));
+        assert_eq!(TextRange::at(origin + relative.index, relative.len), range);
+    }
+
+    #[test]
+    fn test_relative_to_before_origin() {
+        let range = TextRange::at(Utf8Position::new(1, 2), Utf8Position::new(0, 3));
+        assert_eq!(
+            range.relative_to(Utf8Position::new(2, 0)),
+            TextRange::up_to(Utf8Position::new(0, 3))
+        );
+    }
+
+    #[test]
+    fn test_from_composite_range_keeps_end() {
+        let pairs = [
+            ("🐧", "🐧🐧"),
+            ("ab\ncd", "ab\ncdef"),
+            ("ab\ncd", "ab\ncd\nいろは"),
+            ("\n\n🐧x", "\n\n🐧x\n\n\n🐧🐧"),
+        ];
+
+        for &(s, t) in pairs.iter() {
+            let range = TextRange::from(CompositePosition::from(s)..CompositePosition::from(t));
+            let end = range.end();
+
+            assert_eq!(
+                TextRange::<Utf8Index>::from(range).end(),
+                Utf8Index::from(end)
+            );
+            assert_eq!(
+                TextRange::<Utf8Position>::from(range).end(),
+                Utf8Position::from(end)
+            );
+            assert_eq!(
+                TextRange::<Utf16Position>::from(range).end(),
+                Utf16Position::from(end)
+            );
+        }
+    }
+
+    #[test]
+    fn test_strictly_covers_coincident_start() {
+        let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(6));
+        assert!(range.strictly_covers(TextRange::from(Utf8Index::new(2)..Utf8Index::new(5))));
+        assert!(range.strictly_covers(TextRange::empty(Utf8Index::new(2))));
+    }
+
+    #[test]
+    fn test_strictly_covers_coincident_end() {
+        let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(6));
+        assert!(range.covers(range));
+        assert!(!range.strictly_covers(range));
+        assert!(!range.strictly_covers(TextRange::from(Utf8Index::new(3)..Utf8Index::new(6))));
+        assert!(!range.strictly_covers(TextRange::empty(Utf8Index::new(6))));
+    }
+
+    #[test]
+    fn test_strictly_covers_empty() {
+        let empty = TextRange::empty(Utf8Index::new(2));
+        assert!(!empty.strictly_covers(empty));
+    }
+
+    #[test]
+    fn test_lines_within_line() {
+        let text = "abc\ndef";
+        let range = TextRange::from(Utf8Index::new(5)..Utf8Index::new(6));
+        assert_eq!(range.lines(text).collect::<Vec<_>>(), vec![range]);
+    }
+
+    #[test]
+    fn test_lines_empty() {
+        let text = "abc\ndef";
+        let range = TextRange::empty(Utf8Index::new(4));
+        assert_eq!(range.lines(text).collect::<Vec<_>>(), vec![range]);
+    }
+
+    #[test]
+    fn test_lines_multiple() {
+        let text = "abc\n\ndef\nghi";
+        let range = TextRange::from(Utf8Index::new(1)..Utf8Index::new(10));
+        assert_eq!(
+            range
+                .lines(text)
+                .map(|r| &text[r.byte_range()])
+                .collect::<Vec<_>>(),
+            vec!["bc", "", "def", "g"]
+        );
+    }
+
+    #[test]
+    fn test_lines_final_newline() {
+        let text = "abc\ndef\n";
+        let range = TextRange::from(Utf8Index::new(0)..Utf8Index::new(8));
+        assert_eq!(
+            range
+                .lines(text)
+                .map(|r| &text[r.byte_range()])
+                .collect::<Vec<_>>(),
+            text.lines().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_lines_mid_char() {
+        let text = "いろ\nは";
+        let range = TextRange::from(Utf8Index::new(4)..Utf8Index::new(8));
+        assert_eq!(
+            range
+                .lines(text)
+                .map(|r| &text[r.byte_range()])
+                .collect::<Vec<_>>(),
+            vec!["ろ", "は"]
+        );
+    }
+
+    #[test]
+    fn test_into_usize_range() {
+        let range: std::ops::Range<usize> =
+            TextRange::from(Utf8Index::new(2)..Utf8Index::new(5)).into();
+        assert_eq!(range, 2..5);
+    }
+
+    #[test]
+    fn test_try_from_usize_range() {
+        use crate::PositionError;
+        use std::convert::TryFrom;
+
+        assert_eq!(
+            TextRange::try_from(2..5),
+            Ok(TextRange::from(Utf8Index::new(2)..Utf8Index::new(5)))
+        );
+
+        if let Some(too_large) = (u32::MAX as usize).checked_add(1) {
+            assert_eq!(
+                TextRange::try_from(2..too_large),
+                Err(PositionError::OutOfBounds)
+            );
+            assert_eq!(
+                TextRange::from_byte_ranges(vec![0..1, too_large..too_large]),
+                Err(PositionError::OutOfBounds)
+            );
+        }
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn test_into_miette_source_span() {
+        let span = miette::SourceSpan::from(TextRange::at(Utf8Index::new(2), Utf8Index::new(3)));
+        assert_eq!(span.offset(), 2);
+        assert_eq!(span.len(), 3);
+    }
+
+    #[test]
+    fn test_locate_binary_search() {
+        let ranges = [(0, 2), (2, 5), (7, 8)]
+            .iter()
+            .map(|&(start, end)| TextRange::from(Utf8Index::new(start)..Utf8Index::new(end)))
+            .collect::<Vec<_>>();
+        let search = |i: u32| ranges.binary_search_by(|r| r.locate(Utf8Index::new(i)).reverse());
+
+        assert_eq!(search(0), Ok(0));
+        assert_eq!(search(4), Ok(1));
+        assert_eq!(search(5), Err(2));
+        assert_eq!(search(7), Ok(2));
+        assert_eq!(search(8), Err(3));
+    }
+
+    #[test]
+    fn test_union_disjoint() {
+        let first = TextRange::from(Utf8Position::new(0, 2)..Utf8Position::new(0, 4));
+        let second = TextRange::from(Utf8Position::new(1, 0)..Utf8Position::new(1, 4));
+        assert_eq!(first.union(second), None);
+        assert_eq!(second.union(first), None);
+    }
+
+    #[test]
+    fn test_union_containing() {
+        let outer = TextRange::from(Utf8Position::new(0, 2)..Utf8Position::new(3, 0));
+        let inner = TextRange::from(Utf8Position::new(1, 0)..Utf8Position::new(1, 4));
+        assert_eq!(outer.union(inner), Some(outer));
+        assert_eq!(inner.union(outer), Some(outer));
+    }
+
+    #[test]
+    fn test_display_alternate_labels_encoding() {
+        let range = TextRange::at(Utf8Position::new(0, 1), Utf8Position::new(0, 2));
+        assert_eq!(format!("{}", range), "1.2-1.4");
+        assert_eq!(format!("{:#}", range), "1.2-1.4 [utf8]");
+
+        let range = TextRange::at(Utf16Position::new(0, 1), Utf16Position::new(0, 2));
+        assert_eq!(format!("{}", range), "1.2-1.4");
+        assert_eq!(format!("{:#}", range), "1.2-1.4 [utf16]");
+    }
+
+    #[test]
+    fn test_line_count() {
+        fn range_of(s: &str, t: &str) -> TextRange<CompositePosition> {
+            TextRange::from(CompositePosition::from(s)..CompositePosition::from(t))
+        }
+
+        assert_eq!(TextRange::<Utf8Position>::ZERO.line_count(), 1);
+        assert_eq!(range_of("ab", "abcd").line_count(), 1);
+        assert_eq!(range_of("ab", "abcd\n").line_count(), 2);
+        assert_eq!(
+            TextRange::<Utf16Position>::from(range_of("ab\n", "ab\ncd\n\nef")).line_count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_from_char() {
+        assert_eq!(
+            TextRange::<Utf16Position>::from('🐧'),
+            TextRange::up_to(Utf16Position::new(0, 2))
+        );
+        assert_eq!(
+            TextRange::<Utf8Position>::from('\n'),
+            TextRange::up_to(Utf8Position::new(1, 0))
+        );
+    }
+
+    #[test]
+    fn test_to_start_and_to_end() {
+        let range = TextRange::from(Utf8Index::new(2)..Utf8Index::new(5));
+        assert_eq!(range.to_start(), TextRange::collapsed_at(Utf8Index::new(2)));
+        assert_eq!(range.to_end(), TextRange::collapsed_at(Utf8Index::new(5)));
+    }
+
+    #[test]
+    fn test_with_index_and_with_len() {
+        let range = TextRange::at(Utf8Position::new(1, 2), Utf8Position::new(0, 3));
+        assert_eq!(
+            range.with_index(Utf8Position::new(4, 0)),
+            TextRange::at(Utf8Position::new(4, 0), Utf8Position::new(0, 3))
+        );
+        assert_eq!(
+            range.with_len(Utf8Position::new(2, 1)),
+            TextRange::at(Utf8Position::new(1, 2), Utf8Position::new(2, 1))
+        );
+    }
+
+    #[test]
+    fn test_substr() {
+        let text = "fn main() {\n    println!(\"🐧\");\n}\n";
+        let range_of = |start: (u32, u32), end: (u32, u32)| {
+            TextRange::from(Utf8Position::new(start.0, start.1)..Utf8Position::new(end.0, end.1))
+        };
+
+        assert_eq!(range_of((0, 3), (0, 7)).substr(text), Some("main"));
+        assert_eq!(
+            range_of((0, 10), (1, 18)).substr(text),
+            Some("{\n    println!(\"🐧")
+        );
+        assert_eq!(range_of((2, 0), (3, 0)).substr(text), Some("}\n"));
+
+        // In the middle of a character.
+        assert_eq!(range_of((1, 0), (1, 16)).substr(text), None);
+        // Beyond the end of line.
+        assert_eq!(range_of((0, 0), (0, 12)).substr(text), None);
+        // Beyond the end of text.
+        assert_eq!(range_of((0, 0), (4, 0)).substr(text), None);
+    }
+
+    #[test]
+    fn test_intersect_all() {
+        let range =
+            |start: u32, end: u32| TextRange::from(Utf8Index::new(start)..Utf8Index::new(end));
+
+        assert_eq!(TextRange::<Utf8Index>::intersect_all(vec![]), None);
+        assert_eq!(
+            TextRange::intersect_all(vec![range(0, 6), range(2, 8), range(4, 9)]),
+            Some(range(4, 6))
+        );
+
+        // Each overlaps with the next, but the first and the last are disjoint.
+        assert_eq!(
+            TextRange::intersect_all(vec![range(0, 4), range(3, 8), range(6, 9)]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_delta_keeps_end() {
+        let range = TextRange::from(Utf16Position::new(1, 4)..Utf16Position::new(3, 2));
+        assert_eq!(range.start() + range.delta(), range.end());
+    }
+
+    #[test]
+    fn test_translate_back_clamp() {
+        let range = TextRange::at(Utf8Index::new(2), Utf8Index::new(3));
+        assert_eq!(
+            range.translate_back(Utf8Index::new(5)),
+            TextRange::at(Utf8Index::new(0), Utf8Index::new(3))
+        );
+        assert_eq!(
+            range
+                .translate(Utf8Index::new(4))
+                .translate_back(Utf8Index::new(4)),
+            range
+        );
+    }
+
+    #[test]
+    fn test_remove_before_multiline() {
+        fn pos_of(s: &str) -> Utf8Position {
+            Utf8Position::from_str(s)
+        }
+
+        let text = "ab\ncd\nef gh";
+        let range = TextRange::from(pos_of("ab\ncd\nef ")..pos_of(text));
+
+        // Delete "b\ncd\n" entirely before the range.
+        let deleted = TextRange::from(pos_of("a")..pos_of("ab\ncd\n"));
+        assert_eq!(
+            range.remove_before(deleted),
+            TextRange::from(pos_of("aef ")..pos_of("aef gh"))
+        );
+
+        // Delete "ef g" overlapping with the start.
+        let deleted = TextRange::from(pos_of("ab\ncd\n")..pos_of("ab\ncd\nef g"));
+        assert_eq!(
+            range.remove_before(deleted),
+            TextRange::from(pos_of("ab\ncd\n")..pos_of("ab\ncd\nh"))
+        );
+
+        // Delete everything.
+        let deleted = TextRange::up_to(pos_of(text));
+        assert_eq!(range.remove_before(deleted), TextRange::ZERO);
+    }
+
+    #[test]
+    fn test_is_multiline() {
+        fn range_of(s: &str, t: &str) -> TextRange<CompositePosition> {
+            TextRange::from(CompositePosition::from(s)..CompositePosition::from(t))
+        }
+
+        assert!(!TextRange::empty(Utf8Position::new(3, 2)).is_multiline());
+        assert!(!range_of("ab", "abcd").is_multiline());
+        assert!(range_of("ab", "abcd\n").is_multiline());
+        assert!(TextRange::<Utf16Position>::from(range_of("ab", "abcd\nef")).is_multiline());
+    }
+
+    #[test]
+    fn test_convert_to_composite() {
+        let text = "いろは\nにほへと";
+        let range = TextRange::from(Utf8Index::new(3)..Utf8Index::new(13));
+        let converted = range.convert(|index| CompositePosition::resolve(text, index));
+
+        assert_eq!(
+            converted,
+            TextRange::from(CompositePosition::from("い")..CompositePosition::from("いろは\nに"))
+        );
+        assert_eq!(converted.len, CompositePosition::from("ろは\nに"));
+    }
+
+    #[test]
+    fn test_concat_positions_across_lines() {
+        let fragments = ["fn f() {\n", "    🐧();", "\n}\n"];
+        let whole = fragments.concat();
+        let ranges = TextRange::<CompositePosition>::concat_positions(fragments.to_vec());
+
+        assert_eq!(ranges.len(), 3);
+        let mut start = 0;
+        for (range, fragment) in ranges.iter().zip(fragments.iter()) {
+            let end = start + fragment.len();
+            assert_eq!(range.index, CompositePosition::from(&whole[..start]));
+            assert_eq!(range.end(), CompositePosition::from(&whole[..end]));
+            start = end;
+        }
+    }
+
+    #[test]
+    fn test_line_ranges_agree_with_str_lines() {
+        for text in &[
+            "",
+            "\n",
+            "a",
+            "a\n",
+            "a\n\nb",
+            "\nab\ncd\n\n",
+            "🐧\r\nいろは",
+        ] {
+            let lines = TextRange::line_ranges(text)
+                .map(|r| &text[r.byte_range()])
+                .collect::<Vec<_>>();
+            let expected = text.split_terminator('\n').collect::<Vec<_>>();
+            assert_eq!(lines, expected, "{:?}", text);
+
+            let mut reversed = TextRange::line_ranges_rev(text).collect::<Vec<_>>();
+            reversed.reverse();
+            assert_eq!(
+                reversed,
+                TextRange::line_ranges(text).collect::<Vec<_>>(),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[cfg(feature = "text-size")]
+    #[test]
+    fn test_text_size_round_trip() {
+        let range = TextRange::at(Utf8Index::new(2), Utf8Index::new(3));
+        let converted = text_size::TextRange::from(range);
+        assert_eq!(converted, text_size::TextRange::new(2.into(), 5.into()));
+        assert_eq!(TextRange::from(converted), range);
+    }
+
+    #[test]
+    fn test_utf16_units_surrogate_pairs() {
+        let text = "🐧\n🐧🐧 x";
+        let range = TextRange::from(Utf8Index::new(4)..Utf8Index::new(13));
+        assert_eq!(range.utf16_units(text), 5);
+        assert_eq!(TextRange::<Utf8Index>::ZERO.utf16_units(text), 0);
+    }
+
+    #[test]
+    fn test_clamp_len_short() {
+        let range = TextRange::at(Utf8Index::new(5), Utf8Index::new(3));
+        assert_eq!(range.clamp_len(Utf8Index::new(3)), range);
+        assert_eq!(range.clamp_len(Utf8Index::new(80)), range);
+    }
+
+    #[test]
+    fn test_split_by() {
+        let text = "foo bar.baz";
+        let split = |start, end| {
+            TextRange::from(Utf8Index::new(start)..Utf8Index::new(end))
+                .split_by(text, char::is_alphanumeric)
+                .map(|r| &text[r.byte_range()])
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(split(0, 11), vec!["foo", "bar", "baz"]);
+        assert_eq!(split(1, 9), vec!["oo", "bar", "b"]);
+        assert_eq!(split(3, 4), Vec::<&str>::new());
+        assert_eq!(split(5, 5), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_to_utf8_position_range() {
+        let text = "いろは\nにほへと";
+        let range = |start, end| TextRange::from(Utf8Index::new(start)..Utf8Index::new(end));
+
+        assert_eq!(
+            range(6, 13).to_utf8_position_range(text),
+            Some(TextRange::from(
+                Utf8Position::new(0, 6)..Utf8Position::new(1, 3)
+            ))
+        );
+        assert_eq!(
+            range(22, 22).to_utf8_position_range(text),
+            Some(TextRange::empty(Utf8Position::new(1, 12)))
+        );
+        assert_eq!(range(5, 13).to_utf8_position_range(text), None);
+        assert_eq!(range(6, 14).to_utf8_position_range(text), None);
+        assert_eq!(range(22, 23).to_utf8_position_range(text), None);
+    }
+
+    #[test]
+    fn test_underline() {
+        let line = "\tf(いろは, x);";
+        let underline = |start: u32, end: (u32, u32)| {
+            TextRange::from(Utf8Position::new(0, start)..Utf8Position::new(end.0, end.1))
+                .underline(line)
+        };
+
+        assert_eq!(underline(3, (0, 12)), "\t  ^^^");
+        assert_eq!(underline(3, (0, 3)), "\t  ^");
+        assert_eq!(underline(14, (2, 0)), "\t       ^^^");
+        assert_eq!(underline(99, (0, 99)), "\t          ^");
+    }
+
+    #[test]
+    fn test_cmp_by_span() {
+        use std::cmp::Ordering;
+
+        let parent = TextRange::from(Utf8Position::new(1, 0)..Utf8Position::new(3, 0));
+        let child = TextRange::from(Utf8Position::new(1, 0)..Utf8Position::new(1, 5));
+        assert!(parent.covers(child));
+        assert_eq!(parent.cmp_by_span(&child), Ordering::Less);
+        assert_eq!(parent.cmp_by_start_then_len(&child), Ordering::Greater);
+        assert_eq!(parent.cmp_by_start_then_len(&child), parent.cmp(&child));
+    }
+
+    #[test]
+    fn test_overlap_len() {
+        let range = |start, end| TextRange::from(Utf8Index::new(start)..Utf8Index::new(end));
+        assert_eq!(range(2, 6).overlap_len(range(4, 9)), Utf8Index::new(2));
+        assert_eq!(range(2, 9).overlap_len(range(4, 6)), Utf8Index::new(2));
+        assert_eq!(range(2, 4).overlap_len(range(6, 9)), Utf8Index::new(0));
+
+        let range = TextRange::from(Utf8Position::new(0, 3)..Utf8Position::new(2, 1));
+        let other = TextRange::from(Utf8Position::new(1, 0)..Utf8Position::new(3, 0));
+        assert_eq!(range.overlap_len(other), Utf8Position::new(1, 1));
+    }
+
+    #[test]
+    fn test_merge_adjacent_by() {
+        let range = |start: u32, end: u32| {
+            TextRange::from(Utf8Position::new(0, start)..Utf8Position::new(0, end))
+        };
+
+        let merged = TextRange::merge_adjacent_by(
+            vec![
+                (range(0, 3), 'a'),
+                (range(3, 4), 'a'),
+                (range(4, 6), 'b'),
+                (range(7, 8), 'b'),
+            ],
+            |a, b| a == b,
+        );
+        assert_eq!(
+            merged,
+            vec![(range(0, 4), 'a'), (range(4, 6), 'b'), (range(7, 8), 'b')]
+        );
+    }
+
+    #[test]
+    fn test_from_range_inclusive() {
+        let range = TextRange::from(Utf8Index::new(2)..=Utf8Index::new(4));
+        assert_eq!(range.len, Utf8Index::new(3));
+
+        let range = TextRange::from(Utf8Index::new(4)..=Utf8Index::new(2));
+        assert_eq!(range, TextRange::empty(Utf8Index::new(4)));
+
+        let range = TextRange::from(Utf8Index::new(2)..=Utf8Index::new(u32::MAX));
+        assert_eq!(range.end(), Utf8Index::new(u32::MAX));
+    }
+
+    #[test]
+    fn test_same_position() {
+        let cursor = TextRange::empty(Utf8Position::new(1, 4));
+        let selection = TextRange::from(Utf8Position::new(1, 4)..Utf8Position::new(2, 0));
+        assert!(cursor.same_position(selection));
+        assert_ne!(cursor, selection);
+        assert!(!cursor.same_position(TextRange::empty(Utf8Position::new(1, 5))));
+    }
+
+    #[test]
+    fn test_first_difference() {
+        let range = TextRange::from(Utf8Position::new(1, 4)..Utf8Position::new(2, 3));
+        assert_eq!(range.first_difference(range), None);
+
+        let shifted = range.shift_rows(1);
+        assert_eq!(
+            range.first_difference(shifted),
+            Some(Utf8Position::new(1, 4))
+        );
+        assert_eq!(
+            shifted.first_difference(range),
+            Some(Utf8Position::new(1, 4))
+        );
+    }
+
+    #[test]
+    fn test_extend_end_and_start() {
+        let range = TextRange::from(Utf8Position::new(1, 4)..Utf8Position::new(2, 3));
+        assert_eq!(range.extend_end(Utf8Position::new(1, 9)), range);
+        assert_eq!(range.extend_start(Utf8Position::new(2, 0)), range);
+
+        assert_eq!(
+            range.extend_end(Utf8Position::new(3, 1)),
+            TextRange::from(Utf8Position::new(1, 4)..Utf8Position::new(3, 1))
+        );
+        assert_eq!(
+            range.extend_start(Utf8Position::new(0, 7)),
+            TextRange::from(Utf8Position::new(0, 7)..Utf8Position::new(2, 3))
+        );
+    }
+
+    #[test]
+    fn test_indices() {
+        let range = TextRange::from(Utf8Index::new(3)..Utf8Index::new(3));
+        assert_eq!(range.indices().len(), 0);
+
+        let range = TextRange::from(Utf8Index::new(1)..Utf8Index::new(4));
+        assert_eq!(range.indices().next_back(), Some(Utf8Index::new(3)));
+    }
+
+    #[test]
+    fn test_offset_of() {
+        let range = TextRange::from(Utf8Index::new(3)..Utf8Index::new(7));
+        assert_eq!(range.offset_of(Utf8Index::new(3)), Some(Utf8Index::new(0)));
+        assert_eq!(range.offset_of(Utf8Index::new(7)), Some(Utf8Index::new(4)));
+        assert_eq!(range.offset_of(Utf8Index::new(2)), None);
+        assert_eq!(range.offset_of(Utf8Index::new(8)), None);
+    }
+
+    #[test]
+    fn test_center() {
+        let range = |start, end| TextRange::from(Utf8Index::new(start)..Utf8Index::new(end));
+        assert_eq!(range(3, 3).center(), Utf8Index::new(3));
+        assert_eq!(range(2, 6).center(), Utf8Index::new(4));
+        assert_eq!(
+            range(u32::MAX - 2, u32::MAX).center(),
+            Utf8Index::new(u32::MAX - 1)
+        );
+    }
+
+    #[test]
+    fn test_tuple_round_trip() {
+        let range = TextRange::from(Utf8Position::new(1, 4)..Utf8Position::new(2, 3));
+        assert_eq!(TextRange::from_tuple(range.to_tuple()), range);
+
+        let range = TextRange::from(Utf8Index::new(3)..Utf8Index::new(3));
+        assert_eq!(TextRange::from_tuple(range.to_tuple()), range);
+    }
+
+    #[test]
+    fn test_shift_rows() {
+        let range = TextRange::from(Utf8Position::new(1, 4)..Utf8Position::new(2, 3));
+        let shifted = range.shift_rows(2);
+        assert_eq!(shifted.start(), Utf8Position::new(3, 4));
+        assert_eq!(shifted.end(), Utf8Position::new(4, 3));
+        assert_eq!(shifted.shift_rows_up(2), range);
+
+        let range = TextRange::from(Utf16Position::new(1, 4)..Utf16Position::new(1, 6));
+        assert_eq!(
+            range.shift_rows(2),
+            TextRange::from(Utf16Position::new(3, 4)..Utf16Position::new(3, 6))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "strict-overflow")]
+    #[should_panic(expected = "text position overflow")]
+    fn test_shift_rows_strict_overflow() {
+        let range = TextRange::empty(Utf8Position::new(u32::MAX, 0));
+        let _ = range.shift_rows(1);
+    }
+
     #[test]
     fn test_display_zero() {
         assert_eq!(format!("{}", TextRange::<Utf8Position>::ZERO), "1.1-1.1");
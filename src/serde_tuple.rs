@@ -0,0 +1,179 @@
+// LICENSE: CC0-1.0
+
+//! Compact representation of positions as sequences for serde.
+//!
+//! With `serde` feature, positions are serialized as structs by default, e.g. `{"row":1,"column":2}`.
+//! Use this module on a field to serialize it as a sequence instead:
+//!
+//! - `Utf8Position` and `Utf16Position`: `[row, column]`
+//! - `CompositePosition`: `[index, row, column8, column16]`
+//!
+//! Deserialization of the default representation also accepts sequences in the same order,
+//! so data written in either way can be read by default.
+//! The opposite isn't true: this module reads sequences only.
+//! Both reject a `CompositePosition` whose measures are inconsistent.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use text_position_rs::Utf8Position;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Token {
+//!     #[serde(with = "text_position_rs::serde_tuple")]
+//!     pos: Utf8Position,
+//! }
+//!
+//! let token = Token { pos: Utf8Position::new(1, 2) };
+//! assert_eq!(serde_json::to_string(&token).unwrap(), r#"{"pos":[1,2]}"#);
+//! ```
+
+use crate::{CompositePosition, Utf16Position, Utf8Position};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryFrom;
+
+/// Position that has a representation as a tuple.
+pub trait TupleRepr: Sized {
+    type Tuple: Serialize + DeserializeOwned;
+
+    fn to_tuple(&self) -> Self::Tuple;
+
+    /// Return `None` if the tuple doesn't make a valid position.
+    fn from_tuple(tuple: Self::Tuple) -> Option<Self>;
+}
+
+impl TupleRepr for Utf8Position {
+    type Tuple = (u32, u32);
+
+    fn to_tuple(&self) -> Self::Tuple {
+        (self.row, self.column)
+    }
+
+    fn from_tuple((row, column): Self::Tuple) -> Option<Self> {
+        Some(Self::new(row, column))
+    }
+}
+
+impl TupleRepr for Utf16Position {
+    type Tuple = (u32, u32);
+
+    fn to_tuple(&self) -> Self::Tuple {
+        (self.row, self.column)
+    }
+
+    fn from_tuple((row, column): Self::Tuple) -> Option<Self> {
+        Some(Self::new(row, column))
+    }
+}
+
+impl TupleRepr for CompositePosition {
+    type Tuple = (u32, u32, u32, u32);
+
+    fn to_tuple(&self) -> Self::Tuple {
+        (*self).into()
+    }
+
+    /// Return `None` if the measures are inconsistent.
+    fn from_tuple(tuple: Self::Tuple) -> Option<Self> {
+        Self::try_from(tuple).ok()
+    }
+}
+
+/// Serialize a position as a tuple. Used by `#[serde(with = "...")]`.
+pub fn serialize<P: TupleRepr, S: Serializer>(pos: &P, serializer: S) -> Result<S::Ok, S::Error> {
+    pos.to_tuple().serialize(serializer)
+}
+
+/// Deserialize a position from a tuple. Used by `#[serde(with = "...")]`.
+pub fn deserialize<'de, P: TupleRepr, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<P, D::Error> {
+    let tuple = P::Tuple::deserialize(deserializer)?;
+    P::from_tuple(tuple).ok_or_else(|| serde::de::Error::custom("inconsistent position"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CompositePosition, TextPosition, Utf16Position, Utf8Position};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Tuples {
+        #[serde(with = "crate::serde_tuple")]
+        pos8: Utf8Position,
+        #[serde(with = "crate::serde_tuple")]
+        pos16: Utf16Position,
+        #[serde(with = "crate::serde_tuple")]
+        composite: CompositePosition,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let s = "いろは\n🐧";
+        let value = Tuples {
+            pos8: Utf8Position::from_str(s),
+            pos16: Utf16Position::from_str(s),
+            composite: CompositePosition::from_str(s),
+        };
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(
+            json,
+            r#"{"pos8":[1,4],"pos16":[1,2],"composite":[14,1,4,2]}"#
+        );
+        assert_eq!(serde_json::from_str::<Tuples>(&json).unwrap(), value);
+    }
+
+    #[test]
+    fn test_default_reads_both() {
+        let pos = Utf8Position::new(1, 2);
+        assert_eq!(
+            serde_json::to_string(&pos).unwrap(),
+            r#"{"row":1,"column":2}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Utf8Position>(r#"{"row":1,"column":2}"#).unwrap(),
+            pos
+        );
+        assert_eq!(serde_json::from_str::<Utf8Position>("[1,2]").unwrap(), pos);
+    }
+
+    #[test]
+    fn test_default_reads_both_composite() {
+        let pos = CompositePosition::from_str("いろは\n🐧");
+        let json = serde_json::to_string(&pos).unwrap();
+        assert_eq!(json, r#"{"index":14,"row":1,"column8":4,"column16":2}"#);
+
+        let key = |pos: CompositePosition| (pos.index, pos.row, pos.column8, pos.column16);
+        let read = serde_json::from_str::<CompositePosition>(&json).unwrap();
+        assert_eq!(key(read), key(pos));
+        let read = serde_json::from_str::<CompositePosition>("[14,1,4,2]").unwrap();
+        assert_eq!(key(read), key(pos));
+    }
+
+    #[test]
+    fn test_max_round_trip() {
+        let max = CompositePosition::MAX;
+        let key = |pos: CompositePosition| (pos.index, pos.row, pos.column8, pos.column16);
+
+        let json = serde_json::to_string(&max).unwrap();
+        let read = serde_json::from_str::<CompositePosition>(&json).unwrap();
+        assert_eq!(key(read), key(max));
+
+        let value = Tuples {
+            pos8: Utf8Position::MAX,
+            pos16: Utf16Position::MAX,
+            composite: max,
+        };
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(serde_json::from_str::<Tuples>(&json).unwrap(), value);
+    }
+
+    #[test]
+    fn test_inconsistent() {
+        let json = r#"{"pos8":[0,0],"pos16":[0,0],"composite":[1,2,3,4]}"#;
+        assert!(serde_json::from_str::<Tuples>(json).is_err());
+
+        let json = r#"{"index":1,"row":2,"column8":3,"column16":4}"#;
+        assert!(serde_json::from_str::<CompositePosition>(json).is_err());
+        assert!(serde_json::from_str::<CompositePosition>("[1,2,3,4]").is_err());
+    }
+}
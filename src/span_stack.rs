@@ -0,0 +1,111 @@
+// LICENSE: CC0-1.0
+
+use crate::{PositionError, TextPosition, TextRange};
+use std::fmt::{self, Debug, Formatter};
+
+/// Stack of nested ranges, e.g. to build a syntax tree with validation of spans.
+///
+/// A range pushed onto the stack must be covered by the current top (see `TextRange::covers`).
+/// Order of siblings isn't checked: a child can start before its previous sibling.
+///
+/// ```
+/// use text_position_rs::{PositionError, SpanStack, TextRange, Utf8Index};
+///
+/// let range = |start, end| TextRange::from(Utf8Index::new(start)..Utf8Index::new(end));
+///
+/// let mut stack = SpanStack::new();
+/// stack.push(range(0, 10)).unwrap();
+/// stack.push(range(2, 5)).unwrap();
+/// assert_eq!(stack.push(range(4, 6)), Err(PositionError::NotNested));
+///
+/// stack.pop();
+/// stack.push(range(4, 6)).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct SpanStack<P> {
+    stack: Vec<TextRange<P>>,
+}
+
+impl<P: TextPosition> SpanStack<P> {
+    pub fn new() -> Self {
+        Self { stack: vec![] }
+    }
+
+    /// Push a range as a child of the current top.
+    ///
+    /// Fail if the range isn't covered by the top. The stack is unchanged then.
+    pub fn push(&mut self, range: TextRange<P>) -> Result<(), PositionError> {
+        if let Some(top) = self.stack.last() {
+            if !top.clone().covers(range.clone()) {
+                return Err(PositionError::NotNested);
+            }
+        }
+
+        self.stack.push(range);
+        Ok(())
+    }
+
+    /// Remove the top to return to its parent.
+    pub fn pop(&mut self) -> Option<TextRange<P>> {
+        self.stack.pop()
+    }
+
+    /// Innermost range.
+    pub fn top(&self) -> Option<&TextRange<P>> {
+        self.stack.last()
+    }
+
+    /// Number of ranges in the stack.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+}
+
+impl<P: TextPosition> Default for SpanStack<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P> Debug for SpanStack<P>
+where
+    TextRange<P>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.stack).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PositionError, SpanStack, TextRange, Utf8Position};
+
+    fn range(start: (u32, u32), end: (u32, u32)) -> TextRange<Utf8Position> {
+        TextRange::from(Utf8Position::new(start.0, start.1)..Utf8Position::new(end.0, end.1))
+    }
+
+    #[test]
+    fn test_nesting() {
+        let mut stack = SpanStack::new();
+        stack.push(range((0, 0), (3, 1))).unwrap();
+        stack.push(range((1, 4), (2, 5))).unwrap();
+
+        // Same as the parent.
+        stack.push(range((1, 4), (2, 5))).unwrap();
+        stack.pop();
+
+        // Empty range at the end of the parent.
+        stack.push(range((2, 5), (2, 5))).unwrap();
+        stack.pop();
+
+        assert_eq!(
+            stack.push(range((2, 0), (3, 0))),
+            Err(PositionError::NotNested)
+        );
+        assert_eq!(stack.depth(), 2);
+
+        assert_eq!(stack.pop(), Some(range((1, 4), (2, 5))));
+        stack.push(range((2, 0), (3, 0))).unwrap();
+        assert_eq!(stack.top(), Some(&range((2, 0), (3, 0))));
+    }
+}